@@ -0,0 +1,57 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    board::{r#move::Move, Board},
+    MoveGen,
+};
+
+// Opening, middlegame, endgame and tactical positions
+pub const BENCH_FENS: &[&str] = &[
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+];
+
+pub const BENCH_ITERATIONS: u32 = 1000;
+
+// Time taken to generate the legal moves of `board` `BENCH_ITERATIONS` times
+pub fn bench_movegen(move_gen: &MoveGen, board: &Board) -> Duration {
+    let mut moves: Vec<Move> = Vec::with_capacity(256);
+
+    let start = Instant::now();
+    for _ in 0..BENCH_ITERATIONS {
+        moves.clear();
+        move_gen.legal_moves(black_box(board), &mut moves);
+        black_box(&moves);
+    }
+
+    start.elapsed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_fens() {
+        let move_gen = MoveGen::new();
+
+        for fen in BENCH_FENS {
+            let board = Board::from_fen(fen).unwrap();
+
+            let mut moves = Vec::new();
+            move_gen.legal_moves(&board, &mut moves);
+            assert!(!moves.is_empty(), "{fen}");
+
+            assert!(bench_movegen(&move_gen, &board) > Duration::ZERO);
+        }
+    }
+}
@@ -16,6 +16,8 @@ fn main() -> io::Result<()> {
     let king_masks = generation::generate_king_masks();
     let white_pawn_capture_masks = generation::generate_pawn_capture_masks(Color::White);
     let black_pawn_capture_masks = generation::generate_pawn_capture_masks(Color::Black);
+    let white_pawn_push_masks = generation::generate_pawn_push_masks(Color::White);
+    let black_pawn_push_masks = generation::generate_pawn_push_masks(Color::Black);
 
     let mut move_masks = File::create(format!("{path}/move_masks.rs"))?;
     move_masks.write_all(
@@ -27,6 +29,8 @@ pub const ROOK_MOVE_MASKS: [Bitboard; 64] = {rook_masks:#?};
 pub const KING_MOVE_MASKS: [Bitboard; 64] = {king_masks:#?};
 pub const WHITE_PAWN_CAPTURE_MASKS: [Bitboard; 64] = {white_pawn_capture_masks:#?};
 pub const BLACK_PAWN_CAPTURE_MASKS: [Bitboard; 64] = {black_pawn_capture_masks:#?};
+pub const WHITE_PAWN_PUSH_MASKS: [Bitboard; 64] = {white_pawn_push_masks:#?};
+pub const BLACK_PAWN_PUSH_MASKS: [Bitboard; 64] = {black_pawn_push_masks:#?};
 "
         )
         .as_bytes(),
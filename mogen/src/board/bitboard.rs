@@ -35,17 +35,129 @@ impl Bitboard {
 
     pub const EDGES: Bitboard = Bitboard(0xff818181818181ff);
 
+    pub const DIAG_A1H8: Bitboard = Bitboard(0x8040201008040201);
+    pub const ANTI_DIAG_H1A8: Bitboard = Bitboard(0x0102040810204080);
+
+    pub const LIGHT_SQUARES: Bitboard = Bitboard(0x55aa55aa55aa55aa);
+    pub const DARK_SQUARES: Bitboard = Bitboard(0xaa55aa55aa55aa55);
+
+    pub const fn from_u64(bits: u64) -> Bitboard {
+        Bitboard(bits)
+    }
+
+    // Prefer this over the `Deref` to `u64` to make leaving the wrapper explicit
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    // One square towards the eighth rank
+    pub fn north(self) -> Bitboard {
+        Bitboard(self.0 << 8)
+    }
+
+    // One square towards the first rank
+    pub fn south(self) -> Bitboard {
+        Bitboard(self.0 >> 8)
+    }
+
+    // Diagonal steps drop squares that would wrap around to the other edge
+    pub fn north_east(self) -> Bitboard {
+        Bitboard(self.0 << 9) & !Bitboard::A_FILE
+    }
+
+    pub fn north_west(self) -> Bitboard {
+        Bitboard(self.0 << 7) & !Bitboard::H_FILE
+    }
+
+    pub fn south_east(self) -> Bitboard {
+        Bitboard(self.0 >> 7) & !Bitboard::A_FILE
+    }
+
+    pub fn south_west(self) -> Bitboard {
+        Bitboard(self.0 >> 9) & !Bitboard::H_FILE
+    }
+
+    // Squares strictly between two squares sharing a line, empty otherwise
+    pub fn between(a: Square, b: Square) -> Bitboard {
+        let Some((dr, df)) = a.direction_to(b) else {
+            return Bitboard::EMPTY;
+        };
+
+        let mut mask = Bitboard::EMPTY;
+        for square in a.ray(dr, df).take_while(|square| *square != b) {
+            mask |= square.bitboard();
+        }
+
+        mask
+    }
+
+    // The a1-h8 direction diagonal through `square`
+    pub fn diagonal(square: Square) -> Bitboard {
+        let i = square as i32;
+        let offset = (i % 8) - (i / 8);
+
+        // Shifting by whole ranks can't wrap between files
+        if offset >= 0 {
+            Self::DIAG_A1H8 >> (8 * offset) as u32
+        } else {
+            Self::DIAG_A1H8 << (-8 * offset) as u32
+        }
+    }
+
+    // The h1-a8 direction diagonal through `square`
+    pub fn anti_diagonal(square: Square) -> Bitboard {
+        let i = square as i32;
+        let offset = (i % 8) + (i / 8) - 7;
+
+        if offset >= 0 {
+            Self::ANTI_DIAG_H1A8 << (8 * offset) as u32
+        } else {
+            Self::ANTI_DIAG_H1A8 >> (-8 * offset) as u32
+        }
+    }
+
     pub fn subsets(&self) -> Subsets {
         Subsets {
             set: self.0,
             subset: self.0,
+            finished: false,
         }
     }
 
+    // Set squares in index order
+    pub fn squares(&self) -> Squares {
+        Squares { bits: self.0 }
+    }
+
+    pub fn to_squares(&self) -> Vec<Square> {
+        self.squares().collect()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0 == 0
     }
 
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    // The n-th (0-based) set square in index order
+    pub fn nth_set_square(&self, n: usize) -> Option<Square> {
+        let mut bb = *self;
+        for _ in 0..n {
+            if bb.is_empty() {
+                return None;
+            }
+            bb.pop_lsb();
+        }
+
+        if bb.is_empty() {
+            return None;
+        }
+
+        Some(Square::ALL[bb.trailing_zeros() as usize])
+    }
+
     pub fn pop_lsb(&mut self) -> usize {
         let i = self.trailing_zeros();
         self.0 &= self.0 - 1;
@@ -56,27 +168,48 @@ impl Bitboard {
 pub struct Subsets {
     set: u64,
     subset: u64,
+    finished: bool,
 }
 
 impl Iterator for Subsets {
     type Item = Bitboard;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.subset == 0 {
+        if self.finished {
             return None;
         }
 
         let next = self.subset;
-        self.subset = (self.subset - 1) & self.set;
+        self.subset = self.subset.wrapping_sub(1) & self.set;
 
-        if self.subset == 0 {
-            return Some(Bitboard::EMPTY);
+        // The empty set is the last subset
+        if next == 0 {
+            self.finished = true;
         }
 
         Some(Bitboard(next))
     }
 }
 
+pub struct Squares {
+    bits: u64,
+}
+
+impl Iterator for Squares {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+
+        let i = self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+
+        Some(Square::ALL[i])
+    }
+}
+
 impl Display for Bitboard {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let binary = format!("{:064b}", self.0.reverse_bits());
@@ -287,12 +420,130 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_bits() {
+        assert_eq!(Bitboard(0xff).bits(), 0xff);
+        assert_eq!(Bitboard::from_u64(0xff), Bitboard::RANK_1);
+        assert_eq!(
+            Bitboard::from_u64(u64::MAX).bits(),
+            Bitboard::UNIVERSE.bits()
+        );
+    }
+
+    #[test]
+    fn test_shifts() {
+        assert_eq!(Bitboard::RANK_2.north(), Bitboard::RANK_3);
+        assert_eq!(Bitboard::RANK_2.south(), Bitboard::RANK_1);
+        assert_eq!(Bitboard::RANK_8.north(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::RANK_1.south(), Bitboard::EMPTY);
+
+        assert_eq!(Square::E4.bitboard().north_east(), Square::F5.bitboard());
+        assert_eq!(Square::E4.bitboard().north_west(), Square::D5.bitboard());
+        assert_eq!(Square::E4.bitboard().south_east(), Square::F3.bitboard());
+        assert_eq!(Square::E4.bitboard().south_west(), Square::D3.bitboard());
+
+        // No wrapping around the board edges
+        assert_eq!(Bitboard::H_FILE.north_east(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::A_FILE.north_west(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::H_FILE.south_east(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::A_FILE.south_west(), Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn test_between() {
+        assert_eq!(
+            Bitboard::between(Square::A1, Square::D4),
+            Square::B2.bitboard() | Square::C3.bitboard()
+        );
+        assert_eq!(
+            Bitboard::between(Square::E8, Square::E1),
+            Bitboard::E_FILE & !(Square::E1.bitboard() | Square::E8.bitboard())
+        );
+        assert_eq!(Bitboard::between(Square::E1, Square::E2), Bitboard::EMPTY);
+        assert_eq!(Bitboard::between(Square::E1, Square::F3), Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn test_diagonals() {
+        assert_eq!(Bitboard::diagonal(Square::A1), Bitboard::DIAG_A1H8);
+        assert_eq!(Bitboard::diagonal(Square::E5), Bitboard::DIAG_A1H8);
+        assert_eq!(
+            Bitboard::diagonal(Square::A1).to_squares(),
+            [
+                Square::A1,
+                Square::B2,
+                Square::C3,
+                Square::D4,
+                Square::E5,
+                Square::F6,
+                Square::G7,
+                Square::H8
+            ]
+        );
+        assert_eq!(
+            Bitboard::diagonal(Square::G1).to_squares(),
+            [Square::G1, Square::H2]
+        );
+        assert_eq!(
+            Bitboard::diagonal(Square::B7).to_squares(),
+            [Square::A6, Square::B7, Square::C8]
+        );
+        assert_eq!(Bitboard::diagonal(Square::A8), Square::A8.bitboard());
+
+        assert_eq!(
+            Bitboard::anti_diagonal(Square::H1),
+            Bitboard::ANTI_DIAG_H1A8
+        );
+        assert_eq!(
+            Bitboard::anti_diagonal(Square::B1).to_squares(),
+            [Square::B1, Square::A2]
+        );
+        assert_eq!(
+            Bitboard::anti_diagonal(Square::G8).to_squares(),
+            [Square::H7, Square::G8]
+        );
+        assert_eq!(Bitboard::anti_diagonal(Square::H8), Square::H8.bitboard());
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(Bitboard::EMPTY.count(), 0);
+        assert_eq!(Bitboard::A_FILE.count(), 8);
+        assert_eq!(Bitboard(0b1011).count(), 3);
+    }
+
+    #[test]
+    fn test_nth_set_square() {
+        let bb = Bitboard(0b10100);
+
+        assert_eq!(bb.nth_set_square(0), Some(Square::C1));
+        assert_eq!(bb.nth_set_square(1), Some(Square::E1));
+        assert_eq!(bb.nth_set_square(2), None);
+        assert_eq!(Bitboard::EMPTY.nth_set_square(0), None);
+        assert_eq!(Bitboard::RANK_8.nth_set_square(7), Some(Square::H8));
+    }
+
+    #[test]
+    fn test_to_squares() {
+        assert_eq!(
+            (Square::A1.bitboard() | Square::H8.bitboard()).to_squares(),
+            [Square::A1, Square::H8]
+        );
+        assert_eq!(Bitboard::EMPTY.to_squares(), []);
+        assert_eq!(Bitboard::RANK_1.squares().count(), 8);
+    }
+
     #[test]
     fn test_subsets() {
         let bb = Bitboard(0b1101);
-        for subset in bb.subsets() {
-            println!("{:04b}", subset.0);
+        let subsets = bb.subsets().collect::<Vec<Bitboard>>();
+
+        assert_eq!(subsets.len(), 8);
+        for subset in &subsets {
+            assert_eq!(*subset & !bb, Bitboard::EMPTY);
         }
+        assert!(subsets.contains(&Bitboard(0b0001)));
+        assert!(subsets.contains(&Bitboard::EMPTY));
     }
 
     #[test]
@@ -6,6 +6,15 @@ pub enum Color {
 }
 
 impl Color {
+    // Inverse of `color as u8`
+    pub fn from_index(index: u8) -> Option<Color> {
+        match index {
+            0 => Some(Color::White),
+            1 => Some(Color::Black),
+            _ => None,
+        }
+    }
+
     pub fn inverse(&self) -> Self {
         match self {
             Color::White => Color::Black,
@@ -20,3 +29,16 @@ impl Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_index() {
+        for color in [Color::White, Color::Black] {
+            assert_eq!(Color::from_index(color as u8), Some(color));
+        }
+        assert_eq!(Color::from_index(2), None);
+    }
+}
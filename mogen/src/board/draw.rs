@@ -0,0 +1,158 @@
+use crate::{r#static::move_masks::KING_MOVE_MASKS, MoveGen};
+
+use super::{bitboard::Bitboard, color::Color, piece::Piece, square::Square, Board};
+
+// Draws that end the game immediately, other than stalemate which `Status`
+// reports on its own. Repetitions need the game's history, so only `Game`
+// reports them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    SeventyFiveMoveRule,
+    FivefoldRepetition,
+}
+
+// Draws a player may claim, but which don't end the game on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawClaim {
+    FiftyMoveRule,
+    ThreefoldRepetition,
+}
+
+impl Board {
+    // Mate and stalemate take precedence over the seventy-five move rule
+    pub fn is_draw(&self, move_gen: &MoveGen) -> Option<DrawReason> {
+        let reason = self.draw_by_rule()?;
+        move_gen.has_any_legal_move(self).then_some(reason)
+    }
+
+    // The automatic draws that don't depend on the legal moves, for callers
+    // that have already ruled out mate and stalemate
+    pub(crate) fn draw_by_rule(&self) -> Option<DrawReason> {
+        (self.halfmoves >= 150).then_some(DrawReason::SeventyFiveMoveRule)
+    }
+
+    // King, bishop and rook pawns against a lone king holding the promotion
+    // corner, when no bishop can ever cover that corner
+    pub fn is_wrong_bishop_draw(&self) -> bool {
+        [Color::White, Color::Black]
+            .into_iter()
+            .any(|color| self.is_wrong_bishop_draw_for(color))
+    }
+
+    fn is_wrong_bishop_draw_for(&self, attacker: Color) -> bool {
+        let defender = attacker.inverse();
+
+        let pawns = self.bitboard(Piece::Pawn, attacker);
+        let bishops = self.bitboard(Piece::Bishop, attacker);
+        let attacking_pieces = self.bitboard(Piece::King, attacker) | bishops | pawns;
+
+        if pawns.is_empty()
+            || bishops.is_empty()
+            || attacking_pieces != self.color_bitboard(attacker)
+            || self.bitboard(Piece::King, defender) != self.color_bitboard(defender)
+        {
+            return false;
+        }
+
+        let corner = if (pawns & !Bitboard::A_FILE).is_empty() {
+            match attacker {
+                Color::White => Square::A8,
+                Color::Black => Square::A1,
+            }
+        } else if (pawns & !Bitboard::H_FILE).is_empty() {
+            match attacker {
+                Color::White => Square::H8,
+                Color::Black => Square::H1,
+            }
+        } else {
+            return false;
+        };
+
+        let corner_color = if (Bitboard::LIGHT_SQUARES & corner.bitboard()).is_empty() {
+            Bitboard::DARK_SQUARES
+        } else {
+            Bitboard::LIGHT_SQUARES
+        };
+        if !(bishops & corner_color).is_empty() {
+            return false;
+        }
+
+        let corner_zone = KING_MOVE_MASKS[corner as usize] | corner.bitboard();
+        !(self.bitboard(Piece::King, defender) & corner_zone).is_empty()
+    }
+
+    // Only reports draws that aren't already automatic
+    pub fn can_claim_draw(&self, move_gen: &MoveGen) -> Option<DrawClaim> {
+        if self.halfmoves < 100 || self.draw_by_rule().is_some() {
+            return None;
+        }
+
+        move_gen
+            .has_any_legal_move(self)
+            .then_some(DrawClaim::FiftyMoveRule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifty_and_seventy_five_move_rules() {
+        let move_gen = MoveGen::new();
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+
+        assert_eq!(board.can_claim_draw(&move_gen), None);
+        assert_eq!(board.is_draw(&move_gen), None);
+
+        board.halfmoves = 100;
+        assert_eq!(
+            board.can_claim_draw(&move_gen),
+            Some(DrawClaim::FiftyMoveRule)
+        );
+        assert_eq!(board.is_draw(&move_gen), None);
+
+        board.halfmoves = 150;
+        assert_eq!(board.can_claim_draw(&move_gen), None);
+        assert_eq!(
+            board.is_draw(&move_gen),
+            Some(DrawReason::SeventyFiveMoveRule)
+        );
+    }
+
+    #[test]
+    fn test_wrong_bishop_draw() {
+        // The dark-squared bishop can't cover the light a8 corner
+        let board = Board::from_fen("k7/8/8/P7/8/8/3B4/4K3 w - - 0 1").unwrap();
+        assert!(board.is_wrong_bishop_draw());
+
+        // Right bishop
+        let board = Board::from_fen("k7/8/8/P7/8/8/4B3/4K3 w - - 0 1").unwrap();
+        assert!(!board.is_wrong_bishop_draw());
+
+        // Defending king too far from the corner
+        let board = Board::from_fen("8/8/8/P7/8/8/3B2k1/4K3 w - - 0 1").unwrap();
+        assert!(!board.is_wrong_bishop_draw());
+
+        // Not a rook pawn
+        let board = Board::from_fen("k7/8/8/1P6/8/8/3B4/4K3 w - - 0 1").unwrap();
+        assert!(!board.is_wrong_bishop_draw());
+
+        // Same fortress for black, towards h1
+        let board = Board::from_fen("4k3/8/8/8/7p/8/8/4b2K b - - 0 1").unwrap();
+        assert!(board.is_wrong_bishop_draw());
+    }
+
+    #[test]
+    fn test_mate_and_stalemate() {
+        let move_gen = MoveGen::new();
+
+        let mated = Board::from_fen("R3k3/8/4K3/8/8/8/8/8 b - - 150 100").unwrap();
+        assert_eq!(mated.is_draw(&move_gen), None);
+        assert_eq!(mated.can_claim_draw(&move_gen), None);
+
+        let stalemated = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 150 100").unwrap();
+        assert_eq!(stalemated.is_draw(&move_gen), None);
+        assert_eq!(stalemated.can_claim_draw(&move_gen), None);
+    }
+}
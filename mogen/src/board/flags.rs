@@ -1,6 +1,9 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    fmt::Display,
+    ops::{Deref, DerefMut},
+};
 
-use super::color::Color;
+use super::{color::Color, square::Square};
 
 // R - Castling Rights
 // E - Can en passant
@@ -65,6 +68,56 @@ impl Flags {
     }
 }
 
+impl Flags {
+    // Flags only store the en passant file, so the side to move gives its rank
+    pub fn display(self, active_color: Color) -> FlagsDisplay {
+        FlagsDisplay {
+            flags: self,
+            active_color,
+        }
+    }
+}
+
+pub struct FlagsDisplay {
+    flags: Flags,
+    active_color: Color,
+}
+
+impl Display for FlagsDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let flags = self.flags;
+        let mut castling = String::new();
+
+        if flags.kingside(Color::White) {
+            castling.push('K');
+        }
+        if flags.queenside(Color::White) {
+            castling.push('Q');
+        }
+        if flags.kingside(Color::Black) {
+            castling.push('k');
+        }
+        if flags.queenside(Color::Black) {
+            castling.push('q');
+        }
+
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        if flags.can_en_passant() {
+            let rank = match self.active_color {
+                Color::White => 5,
+                Color::Black => 2,
+            };
+            let square = Square::from_coords(rank, flags.en_passant_file());
+            write!(f, "{castling} ep:{square}")
+        } else {
+            write!(f, "{castling}")
+        }
+    }
+}
+
 impl Deref for Flags {
     type Target = u8;
 
@@ -78,3 +131,24 @@ impl DerefMut for Flags {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            format!("{}", Flags(0b00001111).display(Color::White)),
+            "KQkq"
+        );
+        assert_eq!(format!("{}", Flags(0).display(Color::Black)), "-");
+
+        let mut flags = Flags(Flags::WHITE_KINGSIDE | Flags::BLACK_QUEENSIDE);
+        flags.set_en_passant(true);
+        flags.set_en_passant_file(4);
+
+        assert_eq!(format!("{}", flags.display(Color::White)), "Kq ep:e6");
+        assert_eq!(format!("{}", flags.display(Color::Black)), "Kq ep:e3");
+    }
+}
@@ -1,9 +1,18 @@
 pub mod bitboard;
 pub mod color;
+pub mod draw;
+pub mod file;
 pub mod flags;
 pub mod r#move;
+pub mod move_list;
 pub mod piece;
+pub mod pretty;
+pub mod rank;
+pub mod san;
 pub mod square;
+pub mod status;
+pub mod validate;
+pub mod zobrist;
 
 use bitboard::Bitboard;
 use color::Color;
@@ -11,8 +20,18 @@ use flags::Flags;
 use piece::Piece;
 use r#move::Move;
 use square::Square;
-
-use crate::r#static::generation::coords;
+use validate::InvalidPosition;
+
+use crate::{
+    magic::SlidingMoveGen,
+    r#static::{
+        generation::coords,
+        move_masks::{
+            BLACK_PAWN_CAPTURE_MASKS, KING_MOVE_MASKS, KNIGHT_MOVE_MASKS, WHITE_PAWN_CAPTURE_MASKS,
+        },
+    },
+    MoveGen,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParseFenError {
@@ -25,16 +44,32 @@ pub enum ParseFenError {
     BadFullmoves,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MakeMoveError {
+    EmptySource,
+    WrongColor,
+    BadPromotion,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromMovesError {
+    BadUci(String),
+    IllegalMove(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Board {
     pub bitboards: [Bitboard; 8],
     pub active_color: Color,
     pub flags: Flags,
-    pub halfmoves: u8, // Max halfmoves is 100 (50 move rule) or 150 (75 move rule) < u8::MAX
+    pub halfmoves: u8, // Max halfmoves is 100 (50 move rule) or 150 (75 move rule) < u8::MAX, saturates at 255
     pub fullmoves: u16, // u8::MAX < Max fullmoves in one game < u16::MAX
 }
 
 impl Board {
+    // Color bitboards are stored after the six piece bitboards
+    const COLOR_OFFSET: usize = Piece::ALL.len();
+
     pub fn new() -> Board {
         Board {
             bitboards: [Bitboard::EMPTY; 8],
@@ -48,42 +83,15 @@ impl Board {
     pub fn from_fen(fen: &str) -> Result<Self, ParseFenError> {
         let mut board = Board::new();
 
-        let mut parts = fen.split_ascii_whitespace();
+        // Surrounding whitespace never produces an empty field, and non-ASCII
+        // whitespace such as full-width spaces also separates fields
+        let mut parts = fen.split_whitespace();
 
         let Some(position_string) = parts.next() else {
             return Err(ParseFenError::WrongSectionCount);
         };
 
-        let mut rank: i8 = 7;
-        let mut file: i8 = 0;
-
-        for char in position_string.chars() {
-            match char {
-                '0'..='8' => {
-                    let digit = char.to_digit(9).unwrap() as i8;
-                    file += digit;
-                }
-                'p' | 'n' | 'b' | 'r' | 'q' | 'k' | 'P' | 'N' | 'B' | 'R' | 'Q' | 'K' => {
-                    let color = if char.is_uppercase() {
-                        Color::White
-                    } else {
-                        Color::Black
-                    };
-
-                    let piece = Piece::try_from(char).unwrap();
-
-                    let square = Square::ALL[rank as usize * 8 + file as usize];
-                    board.add_piece(piece, color, square);
-
-                    file += 1;
-                }
-                '/' => {
-                    rank -= 1;
-                    file = 0;
-                }
-                _ => return Err(ParseFenError::BadPosition),
-            }
-        }
+        board.parse_position(position_string)?;
 
         let Some(active_color) = parts.next() else {
             return Err(ParseFenError::WrongSectionCount);
@@ -120,32 +128,12 @@ impl Board {
         };
 
         if en_passant != "-" {
-            if en_passant.len() != 2 {
-                return Err(ParseFenError::BadEnPassant);
-            }
-
-            let mut chars = en_passant.chars();
-
-            let Some(file) = chars.next() else {
-                return Err(ParseFenError::BadEnPassant);
-            };
-
-            board.flags.0 |= match file {
-                'a' => 0,
-                'b' => 1,
-                'c' => 2,
-                'd' => 3,
-                'e' => 4,
-                'f' => 5,
-                'g' => 6,
-                'h' => 7,
-                _ => return Err(ParseFenError::BadEnPassant),
-            };
+            let square = Square::try_from(en_passant).map_err(|_| ParseFenError::BadEnPassant)?;
 
-            match chars.next() {
-                Some('3') | Some('6') => (),
-                _ => return Err(ParseFenError::BadEnPassant),
-            }
+            // Must be behind a pawn the side not to move just double pushed
+            board
+                .set_en_passant(Some(square))
+                .map_err(|_| ParseFenError::BadEnPassant)?;
         }
 
         if let Some(halfmoves) = parts.next() {
@@ -171,6 +159,57 @@ impl Board {
         Ok(board)
     }
 
+    // Parses every FEN, pairing each failure with the index of the offending FEN
+    pub fn from_fens(fens: &[&str]) -> Vec<Result<Self, (usize, ParseFenError)>> {
+        fens.iter()
+            .enumerate()
+            .map(|(i, fen)| Board::from_fen(fen).map_err(|err| (i, err)))
+            .collect()
+    }
+
+    pub fn from_position_fen(position: &str) -> Result<Self, ParseFenError> {
+        let mut board = Board::new();
+        board.parse_position(position.trim())?;
+        board.fullmoves = 1;
+
+        Ok(board)
+    }
+
+    fn parse_position(&mut self, position_string: &str) -> Result<(), ParseFenError> {
+        let mut rank: i8 = 7;
+        let mut file: i8 = 0;
+
+        for char in position_string.chars() {
+            match char {
+                '0'..='8' => {
+                    let digit = char.to_digit(9).unwrap() as i8;
+                    file += digit;
+                }
+                'p' | 'n' | 'b' | 'r' | 'q' | 'k' | 'P' | 'N' | 'B' | 'R' | 'Q' | 'K' => {
+                    let color = if char.is_uppercase() {
+                        Color::White
+                    } else {
+                        Color::Black
+                    };
+
+                    let piece = Piece::try_from(char).unwrap();
+
+                    let square = Square::ALL[rank as usize * 8 + file as usize];
+                    self.add_piece(piece, color, square);
+
+                    file += 1;
+                }
+                '/' => {
+                    rank -= 1;
+                    file = 0;
+                }
+                _ => return Err(ParseFenError::BadPosition),
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn fen(&self) -> String {
         let mut fen = String::new();
 
@@ -246,15 +285,7 @@ impl Board {
         fen.push(' ');
 
         // * En passant
-        if self.flags.can_en_passant() {
-            let file = self.flags.en_passant_file();
-            let rank = match self.active_color {
-                Color::White => 2,
-                Color::Black => 5,
-            };
-
-            let square = Square::from_coords(rank, file);
-
+        if let Some(square) = self.en_passant_square() {
             fen.push_str(&square.to_string());
         } else {
             fen.push('-');
@@ -272,6 +303,45 @@ impl Board {
         fen
     }
 
+    // Like `fen`, but only writes the en passant square if a capture there is
+    // actually possible, so equivalent positions produce the same string
+    pub fn minimal_fen(&self) -> String {
+        if self.can_capture_en_passant() {
+            return self.fen();
+        }
+
+        let mut board = *self;
+        board.clear_en_passant();
+        board.fen()
+    }
+
+    // Overwrites this board in place, e.g. a preallocated search stack slot
+    pub fn copy_from(&mut self, other: &Board) {
+        *self = *other;
+    }
+
+    // Half-moves played since the start of the game
+    pub fn ply(&self) -> u32 {
+        (self.fullmoves.saturating_sub(1) as u32) * 2 + (self.active_color == Color::Black) as u32
+    }
+
+    // Ignores the move counters, which don't affect play
+    pub fn same_position(&self, other: &Board) -> bool {
+        self.bitboards == other.bitboards
+            && self.active_color == other.active_color
+            && self.flags == other.flags
+    }
+
+    // `None` if `color` has no king, which hand-built positions may allow
+    pub fn king_square(&self, color: Color) -> Option<Square> {
+        let king = self.bitboard(Piece::King, color);
+        if king.is_empty() {
+            return None;
+        }
+
+        Some(Square::ALL[king.trailing_zeros() as usize])
+    }
+
     pub fn all_pieces(&self) -> Bitboard {
         self.color_bitboard(Color::White) | self.color_bitboard(Color::Black)
     }
@@ -282,6 +352,17 @@ impl Board {
         *self.color_bitboard_mut(color) |= position;
     }
 
+    // Does nothing unless a `piece` of `color` stands on `square`
+    pub fn remove_piece(&mut self, piece: Piece, color: Color, square: Square) {
+        if self.piece_at(square) != Some(piece) || self.color_at(square) != Some(color) {
+            return;
+        }
+
+        let position = !square.bitboard();
+        *self.piece_bitboard_mut(piece) &= position;
+        *self.color_bitboard_mut(color) &= position;
+    }
+
     pub fn piece_bitboard(&self, piece: Piece) -> Bitboard {
         self.bitboards[piece as usize]
     }
@@ -291,11 +372,11 @@ impl Board {
     }
 
     pub fn color_bitboard(&self, color: Color) -> Bitboard {
-        self.bitboards[color as usize + 6]
+        self.bitboards[Self::COLOR_OFFSET + color as usize]
     }
 
     pub fn color_bitboard_mut(&mut self, color: Color) -> &mut Bitboard {
-        &mut self.bitboards[color as usize + 6]
+        &mut self.bitboards[Self::COLOR_OFFSET + color as usize]
     }
 
     pub fn bitboard(&self, piece: Piece, color: Color) -> Bitboard {
@@ -305,29 +386,122 @@ impl Board {
     pub fn piece_at(&self, square: Square) -> Option<Piece> {
         let mask = square.bitboard();
 
-        for (i, bitboard) in self.bitboards.into_iter().enumerate() {
-            if !(bitboard & mask).is_empty() {
-                return Some(Piece::ALL[i]);
-            }
-        }
+        let i = self.bitboards[..Self::COLOR_OFFSET]
+            .iter()
+            .position(|bitboard| !(*bitboard & mask).is_empty())?;
 
-        None
+        Piece::from_index(i as u8)
     }
 
     pub fn color_at(&self, square: Square) -> Option<Color> {
         let mask = square.bitboard();
 
-        let white = self.color_bitboard(Color::White);
-        if !(white & mask).is_empty() {
-            return Some(Color::White);
+        let i = self.bitboards[Self::COLOR_OFFSET..]
+            .iter()
+            .position(|bitboard| !(*bitboard & mask).is_empty())?;
+
+        Color::from_index(i as u8)
+    }
+
+    pub fn piece_count(&self, piece: Piece, color: Color) -> u32 {
+        self.bitboard(piece, color).count()
+    }
+
+    // Indexed by `Piece as usize`
+    pub fn piece_counts(&self, color: Color) -> [u32; 6] {
+        Piece::ALL.map(|piece| self.piece_count(piece, color))
+    }
+
+    // Material signature such as "KRPvKP", with white's pieces first
+    pub fn endgame_signature(&self) -> String {
+        const ORDER: [Piece; 6] = [
+            Piece::King,
+            Piece::Queen,
+            Piece::Rook,
+            Piece::Bishop,
+            Piece::Knight,
+            Piece::Pawn,
+        ];
+
+        let mut signature = String::new();
+
+        for color in [Color::White, Color::Black] {
+            if color == Color::Black {
+                signature.push('v');
+            }
+
+            let counts = self.piece_counts(color);
+            for piece in ORDER {
+                let piece_char = char::from(piece).to_ascii_uppercase();
+                for _ in 0..counts[piece as usize] {
+                    signature.push(piece_char);
+                }
+            }
+        }
+
+        signature
+    }
+
+    // Knight, bishop, rook and queen material in centipawns
+    pub fn non_pawn_material(&self, color: Color) -> i32 {
+        [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen]
+            .into_iter()
+            .map(|piece| self.bitboard(piece, color).count() as i32 * piece.value())
+            .sum()
+    }
+
+    pub fn has_bishop_pair(&self, color: Color) -> bool {
+        self.piece_count(Piece::Bishop, color) >= 2
+    }
+
+    // Pawns one step away from promoting
+    pub fn pawns_near_promotion(&self, color: Color) -> Bitboard {
+        let seventh_rank = match color {
+            Color::White => Bitboard::RANK_7,
+            Color::Black => Bitboard::RANK_2,
+        };
+        self.bitboard(Piece::Pawn, color) & seventh_rank
+    }
+
+    // No enemy pawn ahead of the pawn on `square`, on its own or an adjacent file
+    pub fn is_passed_pawn(&self, square: Square) -> bool {
+        if self.piece_at(square) != Some(Piece::Pawn) {
+            return false;
+        }
+        let Some(color) = self.color_at(square) else {
+            return false;
+        };
+
+        type Shift = fn(Bitboard) -> Bitboard;
+        let (forward, forward_east, forward_west): (Shift, Shift, Shift) = match color {
+            Color::White => (Bitboard::north, Bitboard::north_east, Bitboard::north_west),
+            Color::Black => (Bitboard::south, Bitboard::south_east, Bitboard::south_west),
+        };
+
+        let pawn = square.bitboard();
+        let mut span = forward(pawn) | forward_east(pawn) | forward_west(pawn);
+        loop {
+            let next = span | forward(span);
+            if next == span {
+                break;
+            }
+            span = next;
         }
 
-        let black = self.color_bitboard(Color::Black);
-        if !(black & mask).is_empty() {
-            return Some(Color::Black);
+        (span & self.bitboard(Piece::Pawn, color.inverse())).is_empty()
+    }
+
+    // En passant is only available to the side that didn't just move, so it is
+    // cleared whenever the side to move actually changes
+    pub fn set_side_to_move(&mut self, color: Color) {
+        if color != self.active_color {
+            self.clear_en_passant();
         }
+        self.active_color = color;
+    }
 
-        None
+    pub fn toggle_side(&mut self) {
+        self.set_side_to_move(self.active_color.inverse());
     }
 
     pub fn en_passant_square(&self) -> Option<Square> {
@@ -344,134 +518,1218 @@ impl Board {
         Some(Square::from_coords(rank, file))
     }
 
-    pub fn make_move(&self, mv: Move) -> Self {
-        let mut board = self.clone();
-        board.flags.set_en_passant(false);
-
-        let from = mv.source();
-        let to = mv.target();
-        let promotion = mv.promotion();
-
-        let from_color = if (board.color_bitboard(Color::White) & from.bitboard()).is_empty() {
-            Color::Black
-        } else {
-            Color::White
+    // The square must be on the 6th rank with white to move, or the 3rd with black
+    pub fn set_en_passant(&mut self, square: Option<Square>) -> Result<(), InvalidPosition> {
+        let Some(square) = square else {
+            self.clear_en_passant();
+            return Ok(());
         };
 
-        let Some(from_piece) = board.piece_at(from) else {
-            return board;
+        let rank = match self.active_color {
+            Color::White => Bitboard::RANK_6,
+            Color::Black => Bitboard::RANK_3,
         };
-        let to_piece = board.piece_at(to);
+        if (square.bitboard() & rank).is_empty() {
+            return Err(InvalidPosition::BadEnPassant);
+        }
 
-        // En passant
-        if from_piece == Piece::Pawn {
-            let (from_rank, from_file) = coords(from as u8);
-            let (to_rank, to_file) = coords(to as u8);
+        self.flags.set_en_passant(true);
+        self.flags.set_en_passant_file(square as u8 % 8);
+        Ok(())
+    }
 
-            let rank_diff = from_rank.abs_diff(to_rank);
+    // Also clears the file, so stale bits never leak into comparisons
+    pub fn clear_en_passant(&mut self) {
+        self.flags.set_en_passant(false);
+        self.flags.set_en_passant_file(0);
+    }
 
-            let ep_rank: u8 = match from_color {
-                Color::White => 5,
-                Color::Black => 2,
-            };
-            let ep_file = self.flags.en_passant_file();
+    pub fn is_attacked(&self, square: Square, by: Color, smg: &SlidingMoveGen) -> bool {
+        !self.attackers(square, by, smg).is_empty()
+    }
 
-            // Double move
-            if rank_diff == 2 {
-                board.flags.set_en_passant(true);
-                board.flags.set_en_passant_file(from_file);
-            }
-            // En passant
-            else if self.flags.can_en_passant() && to_rank == ep_rank && to_file == ep_file {
-                let captured_pawn_rank = from_rank;
-                let captured_pawn_file = to_file;
-                let captured_pawn_i = (captured_pawn_rank * 8) + captured_pawn_file;
-                let mask = Bitboard(1 << captured_pawn_i);
+    // Squares of the `by` pieces attacking `square`
+    pub fn attackers(&self, square: Square, by: Color, smg: &SlidingMoveGen) -> Bitboard {
+        let i = square as usize;
+        let blockers = self.all_pieces();
 
-                // Remove pawn
-                *board.piece_bitboard_mut(Piece::Pawn) ^= mask;
-                *board.color_bitboard_mut(from_color.inverse()) ^= mask;
-            }
-        }
+        let queens = self.bitboard(Piece::Queen, by);
+        let diagonal_sliders = self.bitboard(Piece::Bishop, by) | queens;
+        let orthogonal_sliders = self.bitboard(Piece::Rook, by) | queens;
 
-        // From
-        *board.piece_bitboard_mut(from_piece) ^= from.bitboard();
-        *board.color_bitboard_mut(from_color) ^= from.bitboard() | to.bitboard();
+        (pawn_attack_sources(square, by) & self.bitboard(Piece::Pawn, by))
+            | (KNIGHT_MOVE_MASKS[i] & self.bitboard(Piece::Knight, by))
+            | (KING_MOVE_MASKS[i] & self.bitboard(Piece::King, by))
+            | (smg.bishop_moves(square, blockers) & diagonal_sliders)
+            | (smg.rook_moves(square, blockers) & orthogonal_sliders)
+    }
 
-        // To
-        if let Some(piece) = to_piece {
-            *board.piece_bitboard_mut(piece) ^= to.bitboard();
-            *board.color_bitboard_mut(from_color.inverse()) ^= to.bitboard();
-        }
+    // Legal captures by `color` of pieces that nothing defends, winning
+    // material outright. Defenders x-raying through the capturing piece count.
+    pub fn free_captures(&self, color: Color, smg: &SlidingMoveGen) -> Vec<Move> {
+        let mut captures = Vec::new();
+        let blockers = self.all_pieces();
+        let enemies = self.color_bitboard(color.inverse());
 
-        // Replace pieces
-        if let Some(piece) = promotion {
-            *board.piece_bitboard_mut(piece) ^= to.bitboard();
-        } else {
-            *board.piece_bitboard_mut(from_piece) ^= to.bitboard();
+        let back_rank = match color {
+            Color::White => Bitboard::RANK_8,
+            Color::Black => Bitboard::RANK_1,
+        };
+
+        for piece in Piece::ALL {
+            let mut pieces = self.bitboard(piece, color);
+            while !pieces.is_empty() {
+                let source = Square::ALL[pieces.pop_lsb()];
+                let mut targets = piece_attacks(piece, color, source, blockers, smg) & enemies;
+
+                let mut without_source = *self;
+                *without_source.piece_bitboard_mut(piece) ^= source.bitboard();
+                *without_source.color_bitboard_mut(color) ^= source.bitboard();
+
+                while !targets.is_empty() {
+                    let target = Square::ALL[targets.pop_lsb()];
+
+                    if without_source.is_attacked(target, color.inverse(), smg) {
+                        continue;
+                    }
+
+                    let promotions =
+                        if piece == Piece::Pawn && !(back_rank & target.bitboard()).is_empty() {
+                            MoveGen::PROMOTION_ORDER.map(Some).to_vec()
+                        } else {
+                            vec![None]
+                        };
+
+                    for promotion in promotions {
+                        let mv = Move::new(source, target, promotion);
+                        if !self.make_move(mv).is_in_check(color, smg) {
+                            captures.push(mv);
+                        }
+                    }
+                }
+            }
         }
 
-        board.active_color = self.active_color.inverse();
+        captures
+    }
 
-        board
+    // Empty if nothing stands on `square`
+    pub fn attacks_of_piece_on(&self, square: Square, smg: &SlidingMoveGen) -> Bitboard {
+        let (Some(piece), Some(color)) = (self.piece_at(square), self.color_at(square)) else {
+            return Bitboard::EMPTY;
+        };
+
+        piece_attacks(piece, color, square, self.all_pieces(), smg)
     }
-}
 
-impl Default for Board {
-    // Returns a board with the standard starting position loaded
-    fn default() -> Self {
-        Self {
-            bitboards: [
-                Bitboard(0xff00000000ff00),
-                Bitboard(0x4200000000000042),
-                Bitboard(0x2400000000000024),
-                Bitboard(0x8100000000000081),
-                Bitboard(0x800000000000008),
-                Bitboard(0x1000000000000010),
-                Bitboard(0xffff),
-                Bitboard(0xffff000000000000),
-            ],
-            active_color: Color::White,
-            flags: Flags(0b00001111),
-            halfmoves: 0,
-            fullmoves: 1,
+    // Number of pieces of `color` attacking each square
+    pub fn attack_density(&self, color: Color, smg: &SlidingMoveGen) -> [u8; 64] {
+        let mut density = [0; 64];
+        let blockers = self.all_pieces();
+
+        for piece in Piece::ALL {
+            let mut pieces = self.bitboard(piece, color);
+            while !pieces.is_empty() {
+                let square = Square::ALL[pieces.pop_lsb()];
+                let mut attacks = piece_attacks(piece, color, square, blockers, smg);
+
+                while !attacks.is_empty() {
+                    density[attacks.pop_lsb()] += 1;
+                }
+            }
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        density
+    }
 
-    #[test]
-    fn test_fen_startpos() {
-        let fen_board =
-            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
-        let startpos = Board::default();
+    // White attackers minus black attackers on each square
+    pub fn control_map(&self, smg: &SlidingMoveGen) -> [i8; 64] {
+        let white = self.attack_density(Color::White, smg);
+        let black = self.attack_density(Color::Black, smg);
 
-        assert_eq!(fen_board, startpos);
+        std::array::from_fn(|i| white[i] as i8 - black[i] as i8)
     }
 
-    #[test]
-    fn test_piece_at() {
-        let board = Board::default();
+    // Squares the `color` king could move to without being attacked
+    pub fn king_escape_squares(&self, color: Color, smg: &SlidingMoveGen) -> u32 {
+        let Some(king_square) = self.king_square(color) else {
+            return 0;
+        };
 
-        assert_eq!(board.piece_at(Square::A1), Some(Piece::Rook));
-        assert_eq!(board.piece_at(Square::B1), Some(Piece::Knight));
-        assert_eq!(board.piece_at(Square::C1), Some(Piece::Bishop));
-        assert_eq!(board.piece_at(Square::D1), Some(Piece::Queen));
-        assert_eq!(board.piece_at(Square::E1), Some(Piece::King));
-        assert_eq!(board.piece_at(Square::A2), Some(Piece::Pawn));
-        assert_eq!(board.piece_at(Square::E4), None);
+        let without_king = self.without_king(color);
+        let targets = KING_MOVE_MASKS[king_square as usize] & !self.color_bitboard(color);
+        targets
+            .squares()
+            .filter(|square| !without_king.is_attacked(*square, color.inverse(), smg))
+            .count() as u32
     }
 
-    #[test]
-    fn test_make_move_quiet() {
-        let mut initial = Board::new();
-        initial.add_piece(Piece::Rook, Color::White, Square::E4);
-        let after = initial.make_move(Move::new(Square::E4, Square::E7, None));
+    // The board with the `color` king lifted off, so sliders also attack the
+    // squares behind it and pieces it could capture count as defended
+    pub(crate) fn without_king(&self, color: Color) -> Board {
+        let king = self.bitboard(Piece::King, color);
 
-        assert_eq!(
+        let mut board = *self;
+        *board.piece_bitboard_mut(Piece::King) &= !king;
+        *board.color_bitboard_mut(color) &= !king;
+        board
+    }
+
+    // Enemy pieces attacking the `color` king
+    pub fn checkers(&self, color: Color, smg: &SlidingMoveGen) -> Bitboard {
+        let Some(king_square) = self.king_square(color) else {
+            return Bitboard::EMPTY;
+        };
+
+        // The enemy king can't give check, even if it stands next to ours
+        let by = color.inverse();
+        self.attackers(king_square, by, smg) & !self.bitboard(Piece::King, by)
+    }
+
+    // Pieces (of either color) that are the only piece between the `king_color`
+    // king and a slider of `slider_color`
+    fn king_blockers(
+        &self,
+        king_color: Color,
+        slider_color: Color,
+        smg: &SlidingMoveGen,
+    ) -> Bitboard {
+        let Some(king_square) = self.king_square(king_color) else {
+            return Bitboard::EMPTY;
+        };
+
+        // Sliders that would attack the king on an otherwise empty board
+        let queens = self.bitboard(Piece::Queen, slider_color);
+        let mut snipers = (smg.rook_moves(king_square, Bitboard::EMPTY)
+            & (self.bitboard(Piece::Rook, slider_color) | queens))
+            | (smg.bishop_moves(king_square, Bitboard::EMPTY)
+                & (self.bitboard(Piece::Bishop, slider_color) | queens));
+
+        let mut blockers = Bitboard::EMPTY;
+        while !snipers.is_empty() {
+            let sniper = Square::ALL[snipers.pop_lsb()];
+            let between = Bitboard::between(king_square, sniper) & self.all_pieces();
+
+            if between.count() == 1 {
+                blockers |= between;
+            }
+        }
+
+        blockers
+    }
+
+    // Pieces of `color` pinned to their own king
+    pub fn pinned_pieces(&self, color: Color, smg: &SlidingMoveGen) -> Bitboard {
+        self.king_blockers(color, color.inverse(), smg) & self.color_bitboard(color)
+    }
+
+    // A side without a king is never in check
+    pub fn is_in_check(&self, color: Color, smg: &SlidingMoveGen) -> bool {
+        let Some(king_square) = self.king_square(color) else {
+            return false;
+        };
+        self.is_attacked(king_square, color.inverse(), smg)
+    }
+
+    // Whether playing `mv` puts the enemy king in check
+    pub fn gives_check(&self, mv: Move, smg: &SlidingMoveGen) -> bool {
+        let source = mv.source();
+        let target = mv.target();
+
+        let (Some(piece), Some(color)) = (self.piece_at(source), self.color_at(source)) else {
+            return false;
+        };
+
+        let enemy_king = self.bitboard(Piece::King, color.inverse());
+        if enemy_king.is_empty() {
+            return false;
+        }
+
+        // En passant vacates two squares, which may open a line that neither
+        // pawn blocked alone. It is rare enough to just play it out.
+        if self.is_en_passant(mv) {
+            return self.make_move(mv).is_in_check(color.inverse(), smg);
+        }
+
+        let blockers = (self.all_pieces() & !source.bitboard()) | target.bitboard();
+
+        // Castling: the rook lands next to the king and may give check itself
+        if let Some((rook_source, rook_target)) = self.castling_rook_move(mv) {
+            let blockers = (blockers & !rook_source.bitboard()) | rook_target.bitboard();
+            let rook_attacks = piece_attacks(Piece::Rook, color, rook_target, blockers, smg);
+
+            return !(rook_attacks & enemy_king).is_empty();
+        }
+
+        // Discovered check: the only piece between one of our sliders and the
+        // enemy king steps off that line
+        let enemy_king_square = Square::ALL[enemy_king.trailing_zeros() as usize];
+        let discoverers = self.king_blockers(color.inverse(), color, smg);
+        if !(discoverers & source.bitboard()).is_empty()
+            && enemy_king_square.direction_to(source) != enemy_king_square.direction_to(target)
+        {
+            return true;
+        }
+
+        let piece = mv.promotion().unwrap_or(piece);
+        let attacks = piece_attacks(piece, color, target, blockers, smg);
+
+        !(attacks & enemy_king).is_empty()
+    }
+
+    pub fn is_en_passant(&self, mv: Move) -> bool {
+        self.piece_at(mv.source()) == Some(Piece::Pawn)
+            && self.en_passant_square() == Some(mv.target())
+    }
+
+    pub fn is_capture(&self, mv: Move) -> bool {
+        !(self.all_pieces() & mv.target().bitboard()).is_empty() || self.is_en_passant(mv)
+    }
+
+    // Whether a pawn of the side to move attacks the en passant square
+    pub fn can_capture_en_passant(&self) -> bool {
+        let Some(square) = self.en_passant_square() else {
+            return false;
+        };
+
+        // Reverse the capture direction to find the capturing pawns
+        let attackers = match self.active_color {
+            Color::White => BLACK_PAWN_CAPTURE_MASKS[square as usize],
+            Color::Black => WHITE_PAWN_CAPTURE_MASKS[square as usize],
+        };
+
+        !(attackers & self.bitboard(Piece::Pawn, self.active_color)).is_empty()
+    }
+
+    // Also returns the captured piece, which is a pawn for en passant
+    pub fn make_move_with_capture(&self, mv: Move) -> (Self, Option<Piece>) {
+        let captured = if self.is_en_passant(mv) {
+            Some(Piece::Pawn)
+        } else {
+            self.piece_at(mv.target())
+        };
+
+        (self.make_move(mv), captured)
+    }
+
+    // Like `make_move`, but rejects moves that can't apply to this position.
+    // Promotions must be pawn moves onto the back rank, and vice versa.
+    pub fn try_make_move(&self, mv: Move) -> Result<Self, MakeMoveError> {
+        let Some(piece) = self.piece_at(mv.source()) else {
+            return Err(MakeMoveError::EmptySource);
+        };
+
+        if self.color_at(mv.source()) != Some(self.active_color) {
+            return Err(MakeMoveError::WrongColor);
+        }
+
+        let back_rank = match self.active_color {
+            Color::White => Bitboard::RANK_8,
+            Color::Black => Bitboard::RANK_1,
+        };
+        let reaches_back_rank = !(mv.target().bitboard() & back_rank).is_empty();
+        let must_promote = piece == Piece::Pawn && reaches_back_rank;
+
+        if must_promote != mv.promotion().is_some() {
+            return Err(MakeMoveError::BadPromotion);
+        }
+
+        Ok(self.make_move(mv))
+    }
+
+    // Plays UCI moves (e.g. "e2e4") from the start position, checking each is legal
+    pub fn from_moves(moves: &[&str], move_gen: &MoveGen) -> Result<Self, FromMovesError> {
+        let mut board = Board::default();
+        let mut legal = Vec::new();
+
+        for uci in moves {
+            let mv = Move::try_from(*uci).map_err(|_| FromMovesError::BadUci(uci.to_string()))?;
+
+            legal.clear();
+            move_gen.legal_moves(&board, &mut legal);
+            if !legal.contains(&mv) {
+                return Err(FromMovesError::IllegalMove(uci.to_string()));
+            }
+
+            board = board.make_move(mv);
+        }
+
+        Ok(board)
+    }
+
+    // The rook's source and target if `mv` is a king castling from its home square
+    pub fn castling_rook_move(&self, mv: Move) -> Option<(Square, Square)> {
+        if self.piece_at(mv.source()) != Some(Piece::King) {
+            return None;
+        }
+
+        match (mv.source(), mv.target()) {
+            (Square::E1, Square::G1) => Some((Square::H1, Square::F1)),
+            (Square::E1, Square::C1) => Some((Square::A1, Square::D1)),
+            (Square::E8, Square::G8) => Some((Square::H8, Square::F8)),
+            (Square::E8, Square::C8) => Some((Square::A8, Square::D8)),
+            _ => None,
+        }
+    }
+
+    // Castling rights that playing `mv` removes: both of a side's when its king
+    // moves (including castling), and one when a rook leaves or is captured on
+    // its corner
+    pub fn castling_rights_lost(&self, mv: Move) -> u8 {
+        let corner_right = |square| match square {
+            Square::H1 => Flags::WHITE_KINGSIDE,
+            Square::A1 => Flags::WHITE_QUEENSIDE,
+            Square::H8 => Flags::BLACK_KINGSIDE,
+            Square::A8 => Flags::BLACK_QUEENSIDE,
+            _ => 0,
+        };
+
+        let mut lost = corner_right(mv.source()) | corner_right(mv.target());
+
+        if self.piece_at(mv.source()) == Some(Piece::King) {
+            lost |= match self.color_at(mv.source()) {
+                Some(Color::White) => Flags::WHITE_KINGSIDE | Flags::WHITE_QUEENSIDE,
+                _ => Flags::BLACK_KINGSIDE | Flags::BLACK_QUEENSIDE,
+            };
+        }
+
+        lost
+    }
+
+    pub fn make_move(&self, mv: Move) -> Self {
+        let mut board = *self;
+        board.clear_en_passant();
+
+        let from = mv.source();
+        let to = mv.target();
+        let promotion = mv.promotion();
+
+        let (Some(from_piece), Some(from_color)) = (board.piece_at(from), board.color_at(from))
+        else {
+            return board;
+        };
+        let to_piece = board.piece_at(to);
+
+        if from_piece == Piece::Pawn || self.is_capture(mv) {
+            board.halfmoves = 0;
+        } else {
+            board.halfmoves = self.halfmoves.saturating_add(1);
+        }
+
+        // En passant
+        if from_piece == Piece::Pawn {
+            let (from_rank, from_file) = coords(from as u8);
+            let (to_rank, to_file) = coords(to as u8);
+
+            let rank_diff = from_rank.abs_diff(to_rank);
+
+            let ep_rank: u8 = match from_color {
+                Color::White => 5,
+                Color::Black => 2,
+            };
+            let ep_file = self.flags.en_passant_file();
+
+            // Double move
+            if rank_diff == 2 {
+                // Set directly, as `set_en_passant` checks the rank against the
+                // side to move and would drop a double push made out of turn
+                board.flags.set_en_passant(true);
+                board.flags.set_en_passant_file(from_file);
+            }
+            // En passant
+            else if self.flags.can_en_passant() && to_rank == ep_rank && to_file == ep_file {
+                let captured_pawn_rank = from_rank;
+                let captured_pawn_file = to_file;
+                let captured_pawn_i = (captured_pawn_rank * 8) + captured_pawn_file;
+                let mask = Bitboard(1 << captured_pawn_i);
+
+                // Remove pawn
+                *board.piece_bitboard_mut(Piece::Pawn) ^= mask;
+                *board.color_bitboard_mut(from_color.inverse()) ^= mask;
+            }
+        }
+
+        // From
+        *board.piece_bitboard_mut(from_piece) ^= from.bitboard();
+        *board.color_bitboard_mut(from_color) ^= from.bitboard() | to.bitboard();
+
+        // To
+        if let Some(piece) = to_piece {
+            *board.piece_bitboard_mut(piece) ^= to.bitboard();
+            *board.color_bitboard_mut(from_color.inverse()) ^= to.bitboard();
+        }
+
+        if let Some((rook_from, rook_to)) = self.castling_rook_move(mv) {
+            let rook_squares = rook_from.bitboard() | rook_to.bitboard();
+            *board.piece_bitboard_mut(Piece::Rook) ^= rook_squares;
+            *board.color_bitboard_mut(from_color) ^= rook_squares;
+        }
+
+        board.flags.0 &= !self.castling_rights_lost(mv);
+
+        // Replace pieces
+        if let Some(piece) = promotion {
+            *board.piece_bitboard_mut(piece) ^= to.bitboard();
+        } else {
+            *board.piece_bitboard_mut(from_piece) ^= to.bitboard();
+        }
+
+        board.active_color = self.active_color.inverse();
+
+        if from_color == Color::Black {
+            board.fullmoves = self.fullmoves.saturating_add(1);
+        }
+
+        board
+    }
+}
+
+// Squares from which a pawn of color `by` attacks `square`
+pub(crate) fn pawn_attack_sources(square: Square, by: Color) -> Bitboard {
+    let square_bb = square.bitboard();
+
+    match by {
+        Color::White => square_bb.south_east() | square_bb.south_west(),
+        Color::Black => square_bb.north_east() | square_bb.north_west(),
+    }
+}
+
+// Squares attacked by `piece` standing on `square`
+fn piece_attacks(
+    piece: Piece,
+    color: Color,
+    square: Square,
+    blockers: Bitboard,
+    smg: &SlidingMoveGen,
+) -> Bitboard {
+    let i = square as usize;
+
+    match piece {
+        Piece::Pawn => match color {
+            Color::White => WHITE_PAWN_CAPTURE_MASKS[i],
+            Color::Black => BLACK_PAWN_CAPTURE_MASKS[i],
+        },
+        Piece::Knight => KNIGHT_MOVE_MASKS[i],
+        Piece::Bishop => smg.bishop_moves(square, blockers),
+        Piece::Rook => smg.rook_moves(square, blockers),
+        Piece::Queen => smg.bishop_moves(square, blockers) | smg.rook_moves(square, blockers),
+        Piece::King => KING_MOVE_MASKS[i],
+    }
+}
+
+impl Default for Board {
+    // Returns a board with the standard starting position loaded
+    fn default() -> Self {
+        Self {
+            bitboards: [
+                Bitboard(0xff00000000ff00),
+                Bitboard(0x4200000000000042),
+                Bitboard(0x2400000000000024),
+                Bitboard(0x8100000000000081),
+                Bitboard(0x800000000000008),
+                Bitboard(0x1000000000000010),
+                Bitboard(0xffff),
+                Bitboard(0xffff000000000000),
+            ],
+            active_color: Color::White,
+            flags: Flags(0b00001111),
+            halfmoves: 0,
+            fullmoves: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fen_startpos() {
+        let fen_board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let startpos = Board::default();
+
+        assert_eq!(fen_board, startpos);
+    }
+
+    #[test]
+    fn test_from_fen_whitespace() {
+        let expected = Board::default();
+
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\n",
+            "  rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1  ",
+            "\trnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR  w\tKQkq -   0 1\r\n",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR\u{3000}w KQkq - 0\u{3000}1",
+        ];
+
+        for fen in fens {
+            assert_eq!(Board::from_fen(fen), Ok(expected), "{fen:?}");
+        }
+    }
+
+    #[test]
+    fn test_minimal_fen() {
+        let after_e4 = Board::default().make_move(Move::new(Square::E2, Square::E4, None));
+        let without_ep =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+
+        assert_ne!(after_e4.fen(), without_ep.fen());
+        assert_eq!(after_e4.minimal_fen(), without_ep.minimal_fen());
+        assert_eq!(without_ep.minimal_fen(), without_ep.fen());
+
+        // A usable en passant square is kept
+        let board = Board::from_fen("4k3/8/8/8/5p2/8/4P3/4K3 w - - 0 1").unwrap();
+        let board = board.make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.minimal_fen(), board.fen());
+    }
+
+    #[test]
+    fn test_from_fens() {
+        let results = Board::from_fens(&[
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+            "8/8/8/8/4K3/8/8/4k3 b - - 0 1",
+            "8/8/8/8/4K3/8/8/4k3",
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0], Ok(Board::default()));
+        assert_eq!(results[1], Err((1, ParseFenError::BadActiveColor)));
+        assert!(results[2].is_ok());
+        assert_eq!(results[3], Err((3, ParseFenError::WrongSectionCount)));
+    }
+
+    #[test]
+    fn test_from_position_fen() {
+        let board = Board::from_position_fen("8/8/8/8/4K3/8/8/4k3").unwrap();
+
+        assert_eq!(
+            board.bitboard(Piece::King, Color::White),
+            Square::E4.bitboard()
+        );
+        assert_eq!(
+            board.bitboard(Piece::King, Color::Black),
+            Square::E1.bitboard()
+        );
+        assert_eq!(board.active_color, Color::White);
+        assert_eq!(board.flags, Flags(0));
+        assert_eq!(board.halfmoves, 0);
+        assert_eq!(board.fullmoves, 1);
+
+        assert_eq!(
+            Board::from_position_fen("8/8/8/8/4X3/8/8/4k3"),
+            Err(ParseFenError::BadPosition)
+        );
+    }
+
+    #[test]
+    fn test_ply() {
+        let board = Board::default();
+        assert_eq!(board.ply(), 0);
+
+        let board = board.make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.ply(), 1);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 30").unwrap();
+        assert_eq!(board.ply(), 58);
+    }
+
+    #[test]
+    fn test_same_position() {
+        let board = Board::default();
+        let mut other = board;
+        other.fullmoves += 10;
+
+        assert!(board.same_position(&other));
+        assert_ne!(board, other);
+
+        other.toggle_side();
+        assert!(!board.same_position(&other));
+    }
+
+    #[test]
+    fn test_piece_at() {
+        let board = Board::default();
+
+        assert_eq!(board.piece_at(Square::A1), Some(Piece::Rook));
+        assert_eq!(board.piece_at(Square::B1), Some(Piece::Knight));
+        assert_eq!(board.piece_at(Square::C1), Some(Piece::Bishop));
+        assert_eq!(board.piece_at(Square::D1), Some(Piece::Queen));
+        assert_eq!(board.piece_at(Square::E1), Some(Piece::King));
+        assert_eq!(board.piece_at(Square::A2), Some(Piece::Pawn));
+        assert_eq!(board.piece_at(Square::E4), None);
+    }
+
+    #[test]
+    fn test_piece_count() {
+        let board = Board::default();
+
+        assert_eq!(board.piece_count(Piece::Pawn, Color::White), 8);
+        assert_eq!(board.piece_count(Piece::Queen, Color::Black), 1);
+        assert_eq!(board.piece_count(Piece::Knight, Color::Black), 2);
+    }
+
+    #[test]
+    fn test_endgame_signature() {
+        let board = Board::from_fen("8/8/8/4k3/8/8/3Q4/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.endgame_signature(), "KQvK");
+
+        let board = Board::from_fen("8/8/4p3/4k3/8/8/1R1P4/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.endgame_signature(), "KRPvKP");
+
+        assert_eq!(
+            Board::default().endgame_signature(),
+            "KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPPP"
+        );
+    }
+
+    #[test]
+    fn test_non_pawn_material() {
+        let board = Board::default();
+        let expected = 2 * Piece::Knight.value()
+            + 2 * Piece::Bishop.value()
+            + 2 * Piece::Rook.value()
+            + Piece::Queen.value();
+
+        assert_eq!(board.non_pawn_material(Color::White), expected);
+        assert_eq!(board.non_pawn_material(Color::Black), expected);
+
+        let board = Board::from_fen("4k3/pppp4/8/8/8/8/8/3RK3 w - - 0 1").unwrap();
+        assert_eq!(board.non_pawn_material(Color::White), Piece::Rook.value());
+        assert_eq!(board.non_pawn_material(Color::Black), 0);
+    }
+
+    #[test]
+    fn test_has_bishop_pair() {
+        let mut board = Board::new();
+        board.add_piece(Piece::Bishop, Color::White, Square::C1);
+        board.add_piece(Piece::Bishop, Color::Black, Square::C8);
+
+        assert!(!board.has_bishop_pair(Color::White));
+
+        board.add_piece(Piece::Bishop, Color::White, Square::F1);
+
+        assert!(board.has_bishop_pair(Color::White));
+        assert!(!board.has_bishop_pair(Color::Black));
+        assert!(Board::default().has_bishop_pair(Color::Black));
+    }
+
+    #[test]
+    fn test_toggle_side() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let original = board;
+
+        board.toggle_side();
+        assert_eq!(board.active_color, Color::Black);
+        board.toggle_side();
+        assert_eq!(board, original);
+
+        board.set_side_to_move(Color::White);
+        assert_eq!(board, original);
+
+        // Flipping the side drops the en passant square
+        let mut board = original.make_move(Move::new(Square::E2, Square::E4, None));
+        assert!(board.en_passant_square().is_some());
+        board.set_side_to_move(Color::White);
+        assert_eq!(board.en_passant_square(), None);
+    }
+
+    #[test]
+    fn test_free_captures() {
+        let smg = SlidingMoveGen::new();
+
+        // The d5 knight hangs, the f5 bishop is defended by the g6 pawn
+        let board = Board::from_fen("4k3/8/6p1/3n1b2/8/5R2/8/3QK3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.free_captures(Color::White, &smg),
+            vec![Move::new(Square::D1, Square::D5, None)]
+        );
+        assert_eq!(board.free_captures(Color::Black, &smg), Vec::new());
+
+        // The a1 bishop defends e5 through the capturing bishop on c3, but
+        // is itself undefended
+        let board = Board::from_fen("4k3/8/8/4n3/8/2B5/8/b3K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.free_captures(Color::White, &smg),
+            vec![Move::new(Square::C3, Square::A1, None)]
+        );
+    }
+
+    #[test]
+    fn test_attacks_of_piece_on() {
+        let smg = SlidingMoveGen::new();
+        let board = Board::from_fen("4k3/8/8/8/3p4/8/8/3RK3 w - - 0 1").unwrap();
+
+        // Up the d-file to the pawn it attacks, along the rank until the king
+        assert_eq!(
+            board.attacks_of_piece_on(Square::D1, &smg),
+            Square::D2.bitboard()
+                | Square::D3.bitboard()
+                | Square::D4.bitboard()
+                | Square::A1.bitboard()
+                | Square::B1.bitboard()
+                | Square::C1.bitboard()
+                | Square::E1.bitboard()
+        );
+        assert_eq!(
+            board.attacks_of_piece_on(Square::D4, &smg),
+            Square::C3.bitboard() | Square::E3.bitboard()
+        );
+        assert_eq!(board.attacks_of_piece_on(Square::D5, &smg), Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn test_control_map() {
+        let smg = SlidingMoveGen::new();
+        let board = Board::default();
+        let control = board.control_map(&smg);
+
+        // b2, d2 and the b1 knight
+        assert_eq!(control[Square::C3 as usize], 3);
+        // d2 and f2
+        assert_eq!(control[Square::E3 as usize], 2);
+        assert_eq!(control[Square::E4 as usize], 0);
+
+        for square in Square::ALL {
+            let i = square as usize;
+            assert_eq!(control[i], -control[i ^ 56], "{square:?}");
+        }
+    }
+
+    #[test]
+    fn test_is_in_check() {
+        let smg = SlidingMoveGen::new();
+
+        let board = Board::default();
+        assert!(!board.is_in_check(Color::White, &smg));
+        assert!(!board.is_in_check(Color::Black, &smg));
+
+        let board =
+            Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+                .unwrap();
+        assert!(board.is_in_check(Color::White, &smg));
+        assert!(!board.is_in_check(Color::Black, &smg));
+
+        // Knight, pawn and king-less positions
+        let board = Board::from_fen("4k3/8/3N4/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert!(board.is_in_check(Color::Black, &smg));
+        let board = Board::from_fen("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1").unwrap();
+        assert!(board.is_in_check(Color::White, &smg));
+        assert!(!Board::new().is_in_check(Color::White, &smg));
+    }
+
+    #[test]
+    fn test_king_square() {
+        let board = Board::default();
+
+        assert_eq!(board.king_square(Color::White), Some(Square::E1));
+        assert_eq!(board.king_square(Color::Black), Some(Square::E8));
+        assert_eq!(Board::new().king_square(Color::White), None);
+    }
+
+    #[test]
+    fn test_attackers() {
+        let smg = SlidingMoveGen::new();
+        let board = Board::from_fen("7k/8/3n4/3p4/1B2R3/8/4Q3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.attackers(Square::E4, Color::Black, &smg),
+            Square::D5.bitboard() | Square::D6.bitboard()
+        );
+        assert_eq!(
+            board.attackers(Square::E3, Color::White, &smg),
+            Square::E4.bitboard() | Square::E2.bitboard()
+        );
+        assert_eq!(
+            board.attackers(Square::D2, Color::White, &smg),
+            Square::B4.bitboard() | Square::E2.bitboard() | Square::E1.bitboard()
+        );
+        assert_eq!(
+            board.attackers(Square::D6, Color::White, &smg),
+            Square::B4.bitboard()
+        );
+        // The queen is behind the rook
+        assert_eq!(
+            board.attackers(Square::E5, Color::White, &smg),
+            Square::E4.bitboard()
+        );
+        assert_eq!(
+            board.attackers(Square::A1, Color::White, &smg),
+            Bitboard::EMPTY
+        );
+        assert!(board.is_attacked(Square::E3, Color::White, &smg));
+        assert!(!board.is_attacked(Square::A1, Color::White, &smg));
+    }
+
+    #[test]
+    fn test_checkers() {
+        let smg = SlidingMoveGen::new();
+
+        let board = Board::from_fen("k3r3/8/8/8/1b6/3n4/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.checkers(Color::White, &smg),
+            Square::E8.bitboard() | Square::B4.bitboard() | Square::D3.bitboard()
+        );
+        assert_eq!(board.checkers(Color::Black, &smg), Bitboard::EMPTY);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.checkers(Color::White, &smg), Square::D2.bitboard());
+    }
+
+    #[test]
+    fn test_pinned_pieces() {
+        let smg = SlidingMoveGen::new();
+        let board = Board::from_fen("4r3/8/8/b7/4R3/2N5/8/q2NK3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.pinned_pieces(Color::White, &smg),
+            Square::E4.bitboard() | Square::C3.bitboard() | Square::D1.bitboard()
+        );
+        assert_eq!(board.pinned_pieces(Color::Black, &smg), Bitboard::EMPTY);
+
+        // Two pieces on the line means neither is pinned
+        let board = Board::from_fen("4r3/8/8/4n3/4R3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.pinned_pieces(Color::White, &smg), Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn test_gives_check() {
+        let smg = SlidingMoveGen::new();
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K1NR w - - 0 1").unwrap();
+
+        assert!(board.gives_check(Move::new(Square::A1, Square::A8, None), &smg));
+        assert!(!board.gives_check(Move::new(Square::A1, Square::A7, None), &smg));
+        assert!(!board.gives_check(Move::new(Square::G1, Square::F3, None), &smg));
+    }
+
+    #[test]
+    fn test_gives_check_knight_promotion() {
+        let smg = SlidingMoveGen::new();
+        let board = Board::from_fen("8/4P1k1/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        // The promoted piece's geometry counts, not the pawn's or the queen's
+        for (piece, checks) in [
+            (Piece::Knight, true),
+            (Piece::Queen, false),
+            (Piece::Rook, false),
+            (Piece::Bishop, false),
+        ] {
+            let mv = Move::new(Square::E7, Square::E8, Some(piece));
+            assert_eq!(board.gives_check(mv, &smg), checks, "{mv}");
+            assert_eq!(
+                board.make_move(mv).is_in_check(Color::Black, &smg),
+                checks,
+                "{mv}"
+            );
+        }
+
+        // Promoting with a capture
+        let board = Board::from_fen("3r4/4P3/4k3/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(board.gives_check(Move::new(Square::E7, Square::D8, Some(Piece::Knight)), &smg));
+        assert!(!board.gives_check(Move::new(Square::E7, Square::D8, Some(Piece::Queen)), &smg));
+    }
+
+    #[test]
+    fn test_gives_check_discovered() {
+        let smg = SlidingMoveGen::new();
+
+        // The d4 knight blocks the b2 bishop's diagonal to g7
+        let board = Board::from_fen("8/6k1/8/8/3N4/8/1B6/4K3 w - - 0 1").unwrap();
+
+        assert!(board.gives_check(Move::new(Square::D4, Square::B5, None), &smg));
+        assert!(board.gives_check(Move::new(Square::D4, Square::F5, None), &smg));
+        assert!(!board.gives_check(Move::new(Square::B2, Square::C3, None), &smg));
+
+        // Pushing along the file keeps it blocked, capturing off it doesn't
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/K3R3 w - - 0 1").unwrap();
+        assert!(!board.gives_check(Move::new(Square::E4, Square::E5, None), &smg));
+        assert!(board.gives_check(Move::new(Square::E4, Square::D5, None), &smg));
+
+        // Enemy pieces on the line don't discover anything
+        let board = Board::from_fen("8/6k1/8/4p3/3N4/8/1B6/4K3 w - - 0 1").unwrap();
+        assert!(!board.gives_check(Move::new(Square::D4, Square::B5, None), &smg));
+    }
+
+    #[test]
+    fn test_gives_check_en_passant() {
+        let smg = SlidingMoveGen::new();
+
+        // Both pawns leave the fifth rank, unveiling the rook
+        let board = Board::from_fen("8/8/8/R2pP2k/8/8/8/4K3 w - d6 0 1").unwrap();
+        assert!(board.gives_check(Move::new(Square::E5, Square::D6, None), &smg));
+        assert!(!board.gives_check(Move::new(Square::E5, Square::E6, None), &smg));
+
+        // Removing the captured pawn unveils the bishop
+        let board = Board::from_fen("k7/8/8/3pP3/8/8/6B1/4K3 w - d6 0 1").unwrap();
+        assert!(board.gives_check(Move::new(Square::E5, Square::D6, None), &smg));
+    }
+
+    #[test]
+    fn test_gives_check_castling() {
+        let smg = SlidingMoveGen::new();
+
+        // Rook lands on f1, checking the king on f8
+        let board = Board::from_fen("5k2/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        assert!(board.gives_check(Move::new(Square::E1, Square::G1, None), &smg));
+
+        // Rook lands on d8, checking the king on d1
+        let board = Board::from_fen("r3k3/8/8/8/8/8/8/3K4 b q - 0 1").unwrap();
+        assert!(board.gives_check(Move::new(Square::E8, Square::C8, None), &smg));
+
+        let board = Board::from_fen("6k1/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        assert!(!board.gives_check(Move::new(Square::E1, Square::G1, None), &smg));
+    }
+
+    #[test]
+    fn test_fen_en_passant() {
+        let board = Board::from_fen("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3")
+            .unwrap();
+
+        assert!(board.flags.can_en_passant());
+        assert_eq!(board.flags.en_passant_file(), 3);
+        assert_eq!(board.en_passant_square(), Some(Square::D6));
+        assert!(board.is_en_passant(Move::new(Square::E5, Square::D6, None)));
+        assert_eq!(
+            board.fen(),
+            "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3"
+        );
+
+        assert_eq!(
+            Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - i3 0 1"),
+            Err(ParseFenError::BadEnPassant)
+        );
+    }
+
+    #[test]
+    fn test_fen_en_passant_wrong_rank() {
+        // The square must be behind a pawn the side not to move just pushed
+        assert_eq!(
+            Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1"),
+            Err(ParseFenError::BadEnPassant)
+        );
+        assert_eq!(
+            Board::from_fen("4k3/8/8/4p3/8/8/8/4K3 b - e6 0 1"),
+            Err(ParseFenError::BadEnPassant)
+        );
+        assert_eq!(
+            Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - e4 0 1"),
+            Err(ParseFenError::BadEnPassant)
+        );
+
+        let board = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+        assert_eq!(board.en_passant_square(), Some(Square::E3));
+    }
+
+    #[test]
+    fn test_set_en_passant() {
+        let mut board = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+
+        assert_eq!(board.set_en_passant(Some(Square::E3)), Ok(()));
+        assert_eq!(board.en_passant_square(), Some(Square::E3));
+        assert_eq!(board.fen(), "4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1");
+
+        assert_eq!(
+            board.set_en_passant(Some(Square::E6)),
+            Err(InvalidPosition::BadEnPassant)
+        );
+        assert_eq!(board.en_passant_square(), Some(Square::E3));
+
+        board.clear_en_passant();
+        assert_eq!(board.en_passant_square(), None);
+        assert_eq!(board.fen(), "4k3/8/8/8/4P3/8/8/4K3 b - - 0 1");
+
+        board.set_en_passant(Some(Square::E3)).unwrap();
+        assert_eq!(board.set_en_passant(None), Ok(()));
+        assert_eq!(board.en_passant_square(), None);
+    }
+
+    #[test]
+    fn test_is_capture() {
+        let mut board = Board::from_fen("4k3/8/8/3pP3/8/2n5/1P6/4K3 w - - 0 1").unwrap();
+        board.set_en_passant(Some(Square::D6)).unwrap();
+
+        let capture = Move::new(Square::B2, Square::C3, None);
+        let en_passant = Move::new(Square::E5, Square::D6, None);
+        let quiet = Move::new(Square::B2, Square::B3, None);
+
+        assert!(board.is_capture(capture));
+        assert!(!board.is_en_passant(capture));
+
+        assert!(board.is_capture(en_passant));
+        assert!(board.is_en_passant(en_passant));
+
+        assert!(!board.is_capture(quiet));
+        assert!(!board.is_en_passant(quiet));
+    }
+
+    #[test]
+    fn test_king_escape_squares() {
+        let smg = SlidingMoveGen::new();
+
+        // Boxed in by its own pawns, with the back rank covered
+        let board = Board::from_fen("6k1/8/8/8/8/8/5PPP/r5K1 w - - 0 1").unwrap();
+        assert_eq!(board.king_escape_squares(Color::White, &smg), 0);
+
+        let board = Board::from_fen("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(board.king_escape_squares(Color::White, &smg), 2);
+
+        let board = Board::from_fen("7k/8/8/8/3K4/8/8/8 w - - 0 1").unwrap();
+        assert_eq!(board.king_escape_squares(Color::White, &smg), 8);
+
+        // Can't step back along the rook's file, but can take it
+        let board = Board::from_fen("7k/8/8/8/3K4/3r4/8/8 w - - 0 1").unwrap();
+        assert_eq!(board.king_escape_squares(Color::White, &smg), 5);
+        assert_eq!(board.king_escape_squares(Color::Black, &smg), 3);
+    }
+
+    #[test]
+    fn test_make_move_flips_active_color() {
+        let board = Board::default().make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.active_color, Color::Black);
+
+        let board = board.make_move(Move::new(Square::E7, Square::E5, None));
+        assert_eq!(board.active_color, Color::White);
+    }
+
+    #[test]
+    fn test_make_move_castling() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        let after = board.make_move(Move::new(Square::E1, Square::G1, None));
+        assert_eq!(after.piece_at(Square::G1), Some(Piece::King));
+        assert_eq!(after.piece_at(Square::F1), Some(Piece::Rook));
+        assert_eq!(after.piece_at(Square::H1), None);
+        assert_eq!(after.color_at(Square::F1), Some(Color::White));
+        assert!(!after.flags.kingside(Color::White));
+        assert!(!after.flags.queenside(Color::White));
+        assert!(after.flags.kingside(Color::Black));
+
+        let after = board.make_move(Move::new(Square::E1, Square::C1, None));
+        assert_eq!(after.piece_at(Square::C1), Some(Piece::King));
+        assert_eq!(after.piece_at(Square::D1), Some(Piece::Rook));
+        assert_eq!(after.piece_at(Square::A1), None);
+
+        let after = after.make_move(Move::new(Square::E8, Square::G8, None));
+        assert_eq!(
+            after.bitboard(Piece::Rook, Color::Black),
+            Bitboard(0x2100000000000000)
+        );
+        assert_eq!(
+            after.bitboard(Piece::King, Color::Black),
+            Square::G8.bitboard()
+        );
+        assert_eq!(*after.flags & Flags::CASTLING_MASK, 0);
+
+        let after = board
+            .make_move(Move::new(Square::A1, Square::B1, None))
+            .make_move(Move::new(Square::E8, Square::C8, None));
+        assert_eq!(
+            after.bitboard(Piece::Rook, Color::Black),
+            Bitboard(0x8800000000000000)
+        );
+    }
+
+    #[test]
+    fn test_castling_rights_lost() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let white = Flags::WHITE_KINGSIDE | Flags::WHITE_QUEENSIDE;
+
+        let lost = |uci| board.castling_rights_lost(Move::try_from(uci).unwrap());
+        assert_eq!(lost("e1e2"), white);
+        assert_eq!(lost("e1g1"), white);
+        assert_eq!(lost("a1a2"), Flags::WHITE_QUEENSIDE);
+        assert_eq!(lost("h1h5"), Flags::WHITE_KINGSIDE);
+        assert_eq!(
+            lost("a1a8"),
+            Flags::WHITE_QUEENSIDE | Flags::BLACK_QUEENSIDE
+        );
+        assert_eq!(lost("e1d1"), white);
+
+        let after = board.make_move(Move::new(Square::E1, Square::E2, None));
+        assert!(!after.flags.kingside(Color::White));
+        assert!(!after.flags.queenside(Color::White));
+        assert!(after.flags.kingside(Color::Black));
+        assert!(after.flags.queenside(Color::Black));
+
+        let after = board.make_move(Move::new(Square::A1, Square::A2, None));
+        assert!(after.flags.kingside(Color::White));
+        assert!(!after.flags.queenside(Color::White));
+
+        let after = after.make_move(Move::new(Square::E8, Square::D8, None));
+        assert_eq!(*after.flags & Flags::CASTLING_MASK, Flags::WHITE_KINGSIDE);
+    }
+
+    #[test]
+    fn test_make_move_halfmoves() {
+        let board = Board::default().make_move(Move::new(Square::G1, Square::F3, None));
+        assert_eq!(board.halfmoves, 1);
+
+        let board = board.make_move(Move::new(Square::B8, Square::C6, None));
+        assert_eq!(board.halfmoves, 2);
+
+        let board = board.make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.halfmoves, 0);
+
+        let board = Board::from_fen("4k3/8/4n3/8/8/5N2/8/4K3 w - - 7 20").unwrap();
+        let board = board.make_move(Move::new(Square::F3, Square::D4, None));
+        assert_eq!(board.halfmoves, 8);
+
+        // Capture
+        let board = board.make_move(Move::new(Square::E6, Square::D4, None));
+        assert_eq!(board.halfmoves, 0);
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.halfmoves = u8::MAX;
+        assert_eq!(
+            board
+                .make_move(Move::new(Square::E1, Square::E2, None))
+                .halfmoves,
+            u8::MAX
+        );
+    }
+
+    #[test]
+    fn test_make_move_clears_en_passant_file() {
+        let board = Board::default().make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.flags.en_passant_file(), 4);
+
+        let board = board.make_move(Move::new(Square::G8, Square::F6, None));
+        assert!(!board.flags.can_en_passant());
+        assert_eq!(
+            *board.flags & (Flags::FILE_MASK | Flags::EN_PASSANT_MASK),
+            0
+        );
+
+        let expected =
+            Board::from_fen("rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2")
+                .unwrap();
+        assert!(board.same_position(&expected));
+    }
+
+    #[test]
+    fn test_make_move_fullmoves() {
+        let board = Board::default().make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.fullmoves, 1);
+        assert_eq!(board.ply(), 1);
+
+        let board = board.make_move(Move::new(Square::E7, Square::E5, None));
+        assert_eq!(board.fullmoves, 2);
+        assert_eq!(board.ply(), 2);
+        assert_eq!(
+            board.fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
+        );
+    }
+
+    #[test]
+    fn test_make_move_quiet() {
+        let mut initial = Board::new();
+        initial.add_piece(Piece::Rook, Color::White, Square::E4);
+        let after = initial.make_move(Move::new(Square::E4, Square::E7, None));
+
+        assert_eq!(
             after.bitboard(Piece::Rook, Color::White),
             Square::E7.bitboard()
         );
@@ -491,6 +1749,193 @@ mod tests {
         assert_eq!(after.bitboard(Piece::Bishop, Color::Black), Bitboard::EMPTY);
     }
 
+    #[test]
+    fn test_make_move_quiet_promotion() {
+        let mut initial = Board::new();
+        initial.add_piece(Piece::Pawn, Color::White, Square::E7);
+
+        for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+            let after = initial.make_move(Move::new(Square::E7, Square::E8, Some(piece)));
+
+            assert_eq!(after.bitboard(piece, Color::White), Square::E8.bitboard());
+            assert_eq!(after.piece_bitboard(piece), Square::E8.bitboard());
+            assert_eq!(after.piece_bitboard(Piece::Pawn), Bitboard::EMPTY);
+            assert_eq!(after.color_bitboard(Color::White), Square::E8.bitboard());
+            assert_eq!(after.color_bitboard(Color::Black), Bitboard::EMPTY);
+        }
+    }
+
+    #[test]
+    fn test_make_move_capture_promotion_castling_rights() {
+        let board = Board::from_fen("r3k2r/6P1/8/8/8/8/8/4K3 w kq - 0 1").unwrap();
+
+        let board = board.make_move(Move::new(Square::G7, Square::H8, Some(Piece::Queen)));
+        assert_eq!(board.piece_at(Square::H8), Some(Piece::Queen));
+        assert!(!board.flags.kingside(Color::Black));
+        assert!(board.flags.queenside(Color::Black));
+
+        // Taking the a8 rook with the new queen clears the other right
+        let board = board.make_move(Move::new(Square::E8, Square::D7, None));
+        let board = board.make_move(Move::new(Square::H8, Square::A8, None));
+        assert!(!board.flags.queenside(Color::Black));
+    }
+
+    #[test]
+    fn test_make_move_with_capture() {
+        let mut initial = Board::new();
+        initial.add_piece(Piece::Rook, Color::White, Square::E4);
+        initial.add_piece(Piece::Rook, Color::Black, Square::E7);
+
+        let (after, captured) =
+            initial.make_move_with_capture(Move::new(Square::E4, Square::E7, None));
+        assert_eq!(captured, Some(Piece::Rook));
+        assert_eq!(
+            after,
+            initial.make_move(Move::new(Square::E4, Square::E7, None))
+        );
+
+        let (_, captured) = initial.make_move_with_capture(Move::new(Square::E4, Square::E5, None));
+        assert_eq!(captured, None);
+
+        let mut board = Board::new();
+        board.add_piece(Piece::Pawn, Color::White, Square::E2);
+        board.add_piece(Piece::Pawn, Color::Black, Square::D4);
+        let board = board.make_move(Move::new(Square::E2, Square::E4, None));
+
+        let (after, captured) =
+            board.make_move_with_capture(Move::new(Square::D4, Square::E3, None));
+        assert_eq!(captured, Some(Piece::Pawn));
+        assert_eq!(after.color_bitboard(Color::White), Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn test_try_make_move() {
+        let board = Board::from_fen("7k/4P3/8/8/8/8/4P3/R3K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.try_make_move(Move::new(Square::E2, Square::E4, Some(Piece::Queen))),
+            Err(MakeMoveError::BadPromotion)
+        );
+        assert_eq!(
+            board.try_make_move(Move::new(Square::A1, Square::A7, Some(Piece::Queen))),
+            Err(MakeMoveError::BadPromotion)
+        );
+        assert_eq!(
+            board.try_make_move(Move::new(Square::E7, Square::F8, None)),
+            Err(MakeMoveError::BadPromotion)
+        );
+        assert_eq!(
+            board.try_make_move(Move::new(Square::H8, Square::G8, None)),
+            Err(MakeMoveError::WrongColor)
+        );
+        assert_eq!(
+            board.try_make_move(Move::new(Square::E3, Square::E4, None)),
+            Err(MakeMoveError::EmptySource)
+        );
+
+        let mv = Move::new(Square::E7, Square::E8, Some(Piece::Queen));
+        assert_eq!(board.try_make_move(mv), Ok(board.make_move(mv)));
+
+        let mv = Move::new(Square::E2, Square::E4, None);
+        assert_eq!(board.try_make_move(mv), Ok(board.make_move(mv)));
+    }
+
+    #[test]
+    fn test_pawns_near_promotion() {
+        let board = Board::from_fen("4k3/P5p1/8/8/8/8/1P5p/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.pawns_near_promotion(Color::White),
+            Square::A7.bitboard()
+        );
+        assert_eq!(
+            board.pawns_near_promotion(Color::Black),
+            Square::H2.bitboard()
+        );
+        assert!(Board::default()
+            .pawns_near_promotion(Color::White)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_is_passed_pawn() {
+        let board = Board::from_fen("4k3/8/8/1P6/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(board.is_passed_pawn(Square::B5));
+        assert!(!board.is_passed_pawn(Square::B4));
+
+        let board = Board::from_fen("4k3/2p5/8/1P5p/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(!board.is_passed_pawn(Square::B5));
+        assert!(!board.is_passed_pawn(Square::C7));
+        assert!(board.is_passed_pawn(Square::H5));
+
+        // Enemy pawns behind it don't matter
+        let board = Board::from_fen("4k3/8/8/1P6/2p5/8/8/4K3 w - - 0 1").unwrap();
+        assert!(board.is_passed_pawn(Square::B5));
+        assert!(!Board::default().is_passed_pawn(Square::E2));
+    }
+
+    #[test]
+    fn test_remove_piece() {
+        let mut board = Board::new();
+        board.add_piece(Piece::Knight, Color::White, Square::D4);
+        board.remove_piece(Piece::Knight, Color::White, Square::D4);
+
+        assert_eq!(board.piece_at(Square::D4), None);
+        assert_eq!(board.piece_bitboard(Piece::Knight), Bitboard::EMPTY);
+        assert_eq!(board.color_bitboard(Color::White), Bitboard::EMPTY);
+
+        // Removing from an empty square, or a piece that isn't there, leaves
+        // the board untouched
+        let mut board = Board::default();
+        board.remove_piece(Piece::Queen, Color::Black, Square::E4);
+        board.remove_piece(Piece::Queen, Color::White, Square::E2);
+        board.remove_piece(Piece::Pawn, Color::Black, Square::E2);
+        assert_eq!(board, Board::default());
+    }
+
+    #[test]
+    fn test_copy_from() {
+        let kiwipete =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 3 7")
+                .unwrap();
+        let mut board = Board::new();
+
+        board.copy_from(&kiwipete);
+        assert_eq!(board, kiwipete);
+    }
+
+    #[test]
+    fn test_board_is_copy() {
+        let board = Board::default();
+        let copy = board;
+
+        // `board` is still usable after being copied
+        assert_eq!(board, copy);
+        assert_eq!(board.fen(), copy.fen());
+    }
+
+    #[test]
+    fn test_from_moves() {
+        let move_gen = MoveGen::new();
+
+        let sicilian = Board::from_moves(&["e2e4", "c7c5", "g1f3"], &move_gen).unwrap();
+        let expected =
+            Board::from_fen("rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2")
+                .unwrap();
+        assert_eq!(sicilian.bitboards, expected.bitboards);
+        assert_eq!(sicilian.active_color, Color::Black);
+
+        assert_eq!(Board::from_moves(&[], &move_gen), Ok(Board::default()));
+        assert_eq!(
+            Board::from_moves(&["e2e4", "e2e4"], &move_gen),
+            Err(FromMovesError::IllegalMove("e2e4".to_string()))
+        );
+        assert_eq!(
+            Board::from_moves(&["e2e9"], &move_gen),
+            Err(FromMovesError::BadUci("e2e9".to_string()))
+        );
+    }
+
     #[test]
     fn test_white_en_passant() {
         let mut board = Board::new();
@@ -533,6 +1978,53 @@ mod tests {
         assert_eq!(board.color_bitboard(Color::Black), Bitboard::EMPTY);
     }
 
+    #[test]
+    fn test_en_passant_edge_files() {
+        // (capturer, double push source, capture target, bystander pawns of both colors)
+        // from white's point of view; black's cases are the same squares mirrored
+        let cases = [
+            (Square::B5, Square::A7, Square::A6, [Square::H4, Square::C5]),
+            (Square::G5, Square::H7, Square::H6, [Square::A6, Square::F5]),
+            (Square::A5, Square::B7, Square::B6, [Square::H4, Square::C5]),
+            (Square::H5, Square::G7, Square::G6, [Square::A6, Square::F5]),
+        ];
+
+        let mirror = |square: Square| Square::ALL[square as usize ^ 56];
+
+        for (capturer, push_source, target, bystanders) in cases {
+            for color in [Color::White, Color::Black] {
+                let square = |square: Square| match color {
+                    Color::White => square,
+                    Color::Black => mirror(square),
+                };
+
+                let mut board = Board::new();
+                board.active_color = color.inverse();
+                board.add_piece(Piece::Pawn, color, square(capturer));
+                board.add_piece(Piece::Pawn, color.inverse(), square(push_source));
+                board.add_piece(Piece::Pawn, color, square(bystanders[0]));
+                board.add_piece(Piece::Pawn, color.inverse(), square(bystanders[1]));
+
+                let pushed = Square::new(square(push_source).file(), square(capturer).rank());
+                let board = board.make_move(Move::new(square(push_source), pushed, None));
+
+                let capture = Move::new(square(capturer), square(target), None);
+                assert!(board.is_en_passant(capture), "{capture} as {color:?}");
+                let after = board.make_move(capture);
+
+                assert_eq!(after.piece_at(pushed), None, "{capture} as {color:?}");
+                assert_eq!(
+                    after.bitboard(Piece::Pawn, color),
+                    square(target).bitboard() | square(bystanders[0]).bitboard()
+                );
+                assert_eq!(
+                    after.bitboard(Piece::Pawn, color.inverse()),
+                    square(bystanders[1]).bitboard()
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_fen_generation() {
         const FENS: [&str; 3] = [
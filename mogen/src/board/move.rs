@@ -1,18 +1,25 @@
 use std::{
+    cmp::Ordering,
     fmt::Display,
     ops::{Deref, DerefMut},
 };
 
-use super::{bitboard::Bitboard, piece::Piece, square::Square};
+use super::{bitboard::Bitboard, piece::Piece, square::Square, Board};
 
 // F - From
 // T - To
-// D - Data
-// Move: FFFFFFTTTTTTDDDD (16-bit word)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+// K - Kind: 00 for plain moves, 01 for promotions, 10 and 11 are reserved
+// P - Promotion piece: knight, bishop, rook or queen
+// Move: FFFFFFTTTTTTKKPP (16-bit word)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Move(u16);
 
 impl Move {
+    pub const FROM_MASK: u16 = 0b1111110000000000;
+    pub const TO_MASK: u16 = 0b0000001111110000;
+    pub const KIND_MASK: u16 = 0b0000000000001100;
+    pub const PROMO_MASK: u16 = 0b0000000000000011;
+
     pub fn new(source: Square, target: Square, promotion: Option<Piece>) -> Move {
         let source = (source as u16) << 10;
         let target = (target as u16) << 4;
@@ -23,34 +30,103 @@ impl Move {
         Move(source | target | promotion)
     }
 
+    // UCI "0000", encoded as a1a1 which no real move can be
+    pub fn null() -> Move {
+        Move(0)
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.0 == 0
+    }
+
+    // Rejects reserved kinds and plain moves with promotion bits set, which
+    // would otherwise be misread as plain moves
+    pub fn from_raw_checked(raw: u16) -> Option<Move> {
+        let data = raw & (Self::KIND_MASK | Self::PROMO_MASK);
+        if data != 0 && Piece::from_promotion_bits(data).is_none() {
+            return None;
+        }
+
+        Some(Move(raw))
+    }
+
+    pub fn raw(&self) -> u16 {
+        self.0
+    }
+
     pub fn source(&self) -> Square {
-        Square::ALL[(self.0 >> 10) as usize]
+        Square::ALL[((self.0 & Self::FROM_MASK) >> 10) as usize]
     }
 
     pub fn target(&self) -> Square {
-        Square::ALL[0b111111 & (self.0 >> 4) as usize]
+        Square::ALL[((self.0 & Self::TO_MASK) >> 4) as usize]
     }
 
     pub fn promotion(&self) -> Option<Piece> {
-        match self.0 & 0b1111 {
-            1 => Some(Piece::Knight),
-            2 => Some(Piece::Bishop),
-            4 => Some(Piece::Rook),
-            8 => Some(Piece::Queen),
-            _ => None,
-        }
+        Piece::from_promotion_bits(self.0 & (Self::KIND_MASK | Self::PROMO_MASK))
     }
 
     pub fn bitboard(&self) -> Bitboard {
         self.source().bitboard() | self.target().bitboard()
     }
+
+    // Square of the piece captured by this move, which for en passant is the
+    // pawn behind the target square
+    pub fn captured_square(&self, board: &Board) -> Option<Square> {
+        if board.is_en_passant(*self) {
+            return Some(Square::new(self.target().file(), self.source().rank()));
+        }
+
+        if (board.all_pieces() & self.target().bitboard()).is_empty() {
+            None
+        } else {
+            Some(self.target())
+        }
+    }
+
+    // Flips the move vertically (e2e4 <-> e7e5), keeping any promotion
+    pub fn mirror(&self) -> Move {
+        let source = Square::ALL[self.source() as usize ^ 56];
+        let target = Square::ALL[self.target() as usize ^ 56];
+        Move::new(source, target, self.promotion())
+    }
+}
+
+// Moves order by source, then target, then promotion: a plain move first,
+// then queen, rook, bishop and knight. The raw promotion bits would put the
+// queen last, so each promotion gets a fixed rank instead.
+impl Ord for Move {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let promotion_rank = |mv: &Move| match mv.promotion() {
+            None => 0,
+            Some(Piece::Queen) => 1,
+            Some(Piece::Rook) => 2,
+            Some(Piece::Bishop) => 3,
+            Some(Piece::Knight) => 4,
+            Some(Piece::Pawn | Piece::King) => unreachable!("never a promotion"),
+        };
+
+        (self.0 >> 4)
+            .cmp(&(other.0 >> 4))
+            .then_with(|| promotion_rank(self).cmp(&promotion_rank(other)))
+    }
+}
+
+impl PartialOrd for Move {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Display for Move {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_null() {
+            return write!(f, "0000");
+        }
+
         if let Some(piece) = self.promotion() {
             let promotion_char = match piece {
-                Piece::Knight => 'k',
+                Piece::Knight => 'n',
                 Piece::Bishop => 'b',
                 Piece::Rook => 'r',
                 Piece::Queen => 'q',
@@ -70,6 +146,10 @@ impl TryFrom<&str> for Move {
     type Error = ParseMoveError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if value == "0000" {
+            return Ok(Move::null());
+        }
+
         let length = value.len();
         if length != 4 && length != 5 {
             return Err(ParseMoveError);
@@ -118,6 +198,62 @@ impl DerefMut for Move {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_captured_square() {
+        let board =
+            Board::from_fen("rnbqkb1r/ppp1p1pp/5n2/3pP2Q/5p2/8/PPPP1PPP/RNB1KBNR w KQkq - 0 3")
+                .unwrap();
+        let board = board.make_move(Move::new(Square::G2, Square::G4, None));
+
+        // fxg3 e.p. captures the pawn on g4
+        let mv = Move::new(Square::F4, Square::G3, None);
+        assert_eq!(mv.captured_square(&board), Some(Square::G4));
+
+        let mv = Move::new(Square::F6, Square::H5, None);
+        assert_eq!(mv.captured_square(&board), Some(Square::H5));
+
+        let mv = Move::new(Square::F6, Square::G4, None);
+        assert_eq!(mv.captured_square(&board), Some(Square::G4));
+
+        let mv = Move::new(Square::F6, Square::E4, None);
+        assert_eq!(mv.captured_square(&board), None);
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut moves = [Piece::Knight, Piece::Rook, Piece::Queen, Piece::Bishop]
+            .map(|piece| Move::new(Square::B7, Square::B8, Some(piece)))
+            .to_vec();
+        moves.push(Move::new(Square::A7, Square::A8, Some(Piece::Knight)));
+        moves.push(Move::new(Square::B7, Square::A8, Some(Piece::Knight)));
+        moves.sort();
+
+        assert_eq!(
+            moves,
+            vec![
+                Move::new(Square::A7, Square::A8, Some(Piece::Knight)),
+                Move::new(Square::B7, Square::A8, Some(Piece::Knight)),
+                Move::new(Square::B7, Square::B8, Some(Piece::Queen)),
+                Move::new(Square::B7, Square::B8, Some(Piece::Rook)),
+                Move::new(Square::B7, Square::B8, Some(Piece::Bishop)),
+                Move::new(Square::B7, Square::B8, Some(Piece::Knight)),
+            ]
+        );
+
+        assert!(Move::new(Square::E2, Square::E4, None) < Move::new(Square::E2, Square::E5, None));
+    }
+
+    #[test]
+    fn test_null_move() {
+        let mv = Move::try_from("0000").unwrap();
+        assert!(mv.is_null());
+        assert_eq!(mv, Move::null());
+        assert_eq!(mv.to_string(), "0000");
+
+        assert!(!Move::try_from("e2e4").unwrap().is_null());
+        assert!(Move::try_from("0000q").is_err());
+    }
+
     #[test]
     fn test_new_move() {
         let mv = Move::new(Square::E2, Square::E4, Some(Piece::Rook));
@@ -127,6 +263,60 @@ mod tests {
         assert_eq!(mv.promotion(), Some(Piece::Rook));
     }
 
+    #[test]
+    fn test_masks() {
+        let masks = [
+            Move::FROM_MASK,
+            Move::TO_MASK,
+            Move::PROMO_MASK,
+            Move::KIND_MASK,
+        ];
+
+        for (i, a) in masks.iter().enumerate() {
+            for b in &masks[i + 1..] {
+                assert_eq!(a & b, 0);
+            }
+        }
+        assert_eq!(masks.iter().fold(0, |all, mask| all | mask), u16::MAX);
+    }
+
+    #[test]
+    fn test_raw() {
+        let mv = Move::new(Square::B7, Square::A8, Some(Piece::Rook));
+
+        assert_eq!(Move::from_raw_checked(mv.raw()), Some(mv));
+        assert_eq!(
+            Move::from_raw_checked(Move::null().raw()),
+            Some(Move::null())
+        );
+
+        // Reserved kinds
+        assert_eq!(Move::from_raw_checked(mv.raw() | 0b1000), None);
+        assert_eq!(
+            Move::from_raw_checked((mv.raw() & !Move::KIND_MASK) | 0b1000),
+            None
+        );
+
+        // A plain move carrying a promotion piece
+        let plain = Move::new(Square::E2, Square::E4, None);
+        assert_eq!(Move::from_raw_checked(plain.raw() | 0b0010), None);
+    }
+
+    #[test]
+    fn test_mirror() {
+        assert_eq!(
+            Move::new(Square::E2, Square::E4, None).mirror(),
+            Move::new(Square::E7, Square::E5, None)
+        );
+        assert_eq!(
+            Move::new(Square::B7, Square::A8, Some(Piece::Queen)).mirror(),
+            Move::new(Square::B2, Square::A1, Some(Piece::Queen))
+        );
+
+        let mv = Move::new(Square::G1, Square::F3, None);
+        assert_eq!(mv.mirror().mirror(), mv);
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(
@@ -143,7 +333,18 @@ mod tests {
         );
         assert_eq!(
             format!("{}", Move::new(Square::B7, Square::B8, Some(Piece::Knight))),
-            "b7b8k"
+            "b7b8n"
         );
     }
+
+    #[test]
+    fn test_promotion_round_trip() {
+        let mv = Move::new(Square::A7, Square::A8, Some(Piece::Knight));
+        assert_eq!(Move::try_from(mv.to_string().as_str()).ok(), Some(mv));
+
+        for piece in [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight] {
+            let mv = Move::new(Square::H2, Square::G1, Some(piece));
+            assert_eq!(Move::try_from(mv.to_string().as_str()).ok(), Some(mv));
+        }
+    }
 }
@@ -0,0 +1,76 @@
+use super::r#move::Move;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveKind {
+    Quiet,
+    Capture,
+}
+
+// Moves tagged with their kind, so ordering doesn't have to look at the board again
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveList {
+    moves: Vec<(Move, MoveKind)>,
+}
+
+impl MoveList {
+    pub fn new() -> MoveList {
+        MoveList { moves: Vec::new() }
+    }
+
+    pub fn push(&mut self, mv: Move, kind: MoveKind) {
+        self.moves.push((mv, kind));
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.moves.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Move, MoveKind)> + '_ {
+        self.moves.iter().copied()
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.iter().map(|(mv, _)| mv)
+    }
+
+    pub fn captures(&self) -> impl Iterator<Item = Move> + '_ {
+        self.of_kind(MoveKind::Capture)
+    }
+
+    pub fn quiets(&self) -> impl Iterator<Item = Move> + '_ {
+        self.of_kind(MoveKind::Quiet)
+    }
+
+    fn of_kind(&self, kind: MoveKind) -> impl Iterator<Item = Move> + '_ {
+        self.iter()
+            .filter(move |(_, move_kind)| *move_kind == kind)
+            .map(|(mv, _)| mv)
+    }
+}
+
+// Anything the move generators can push into, so owned lists such as a
+// `SmallVec` are filled directly instead of being copied out of a `Vec`
+pub trait MoveSink {
+    fn push(&mut self, mv: Move);
+}
+
+impl MoveSink for Vec<Move> {
+    fn push(&mut self, mv: Move) {
+        Vec::push(self, mv);
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array<Item = Move>> MoveSink for smallvec::SmallVec<A> {
+    fn push(&mut self, mv: Move) {
+        smallvec::SmallVec::push(self, mv);
+    }
+}
@@ -19,16 +19,46 @@ impl Piece {
         Piece::King,
     ];
 
-    pub fn promotion_mask(&self) -> u16 {
+    // Inverse of `piece as u8`
+    pub fn from_index(index: u8) -> Option<Piece> {
+        Piece::ALL.get(index as usize).copied()
+    }
+
+    // Material value in centipawns
+    pub fn value(&self) -> i32 {
         match self {
-            Piece::Pawn => 0,
-            Piece::Knight => 1,
-            Piece::Bishop => 2,
-            Piece::Rook => 4,
-            Piece::Queen => 8,
+            Piece::Pawn => 100,
+            Piece::Knight => 320,
+            Piece::Bishop => 330,
+            Piece::Rook => 500,
+            Piece::Queen => 900,
             Piece::King => 0,
         }
     }
+
+    // Low nibble of a `Move` for each promotable piece: the promotion kind
+    // followed by the piece
+    pub const PROMOTION_BITS: [(Piece, u16); 4] = [
+        (Piece::Knight, 0b0100),
+        (Piece::Bishop, 0b0101),
+        (Piece::Rook, 0b0110),
+        (Piece::Queen, 0b0111),
+    ];
+
+    // 0 for pieces that can't be promoted to
+    pub fn promotion_mask(&self) -> u16 {
+        Self::PROMOTION_BITS
+            .iter()
+            .find(|(piece, _)| piece == self)
+            .map_or(0, |(_, bits)| *bits)
+    }
+
+    pub fn from_promotion_bits(bits: u16) -> Option<Piece> {
+        Self::PROMOTION_BITS
+            .iter()
+            .find(|(_, piece_bits)| *piece_bits == bits)
+            .map(|(piece, _)| *piece)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -62,3 +92,31 @@ impl From<Piece> for char {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_index() {
+        for piece in Piece::ALL {
+            assert_eq!(Piece::from_index(piece as u8), Some(piece));
+        }
+        assert_eq!(Piece::from_index(6), None);
+    }
+
+    #[test]
+    fn test_promotion_bits() {
+        for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+            assert_eq!(
+                Piece::from_promotion_bits(piece.promotion_mask()),
+                Some(piece)
+            );
+        }
+
+        assert_eq!(Piece::Pawn.promotion_mask(), 0);
+        assert_eq!(Piece::King.promotion_mask(), 0);
+        assert_eq!(Piece::from_promotion_bits(0), None);
+        assert_eq!(Piece::from_promotion_bits(3), None);
+    }
+}
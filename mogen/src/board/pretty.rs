@@ -0,0 +1,107 @@
+use super::{color::Color, square::Square, Board};
+
+impl Board {
+    // One line per rank, white pieces in uppercase and empty squares as '.'
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+
+        for rank in (0..8).rev() {
+            out.push_str(&self.pretty_rank(rank));
+            out.push('\n');
+        }
+        out.push_str("  a b c d e f g h\n");
+
+        out
+    }
+
+    // Both boards side by side, followed by a grid marking differing squares with 'x'
+    pub fn diff(&self, other: &Board) -> String {
+        let mut out = String::new();
+
+        for rank in (0..8).rev() {
+            let marks = (0..8)
+                .map(|file| {
+                    let square = Square::from_coords(rank, file);
+                    let differs = self.piece_at(square) != other.piece_at(square)
+                        || self.color_at(square) != other.color_at(square);
+                    if differs {
+                        "x"
+                    } else {
+                        "."
+                    }
+                })
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            out.push_str(&format!(
+                "{}   {}   {} {marks}\n",
+                self.pretty_rank(rank),
+                other.pretty_rank(rank),
+                rank + 1
+            ));
+        }
+        out.push_str("  a b c d e f g h     a b c d e f g h     a b c d e f g h\n");
+
+        out
+    }
+
+    fn pretty_rank(&self, rank: u8) -> String {
+        let mut row = format!("{}", rank + 1);
+
+        for file in 0..8 {
+            let square = Square::from_coords(rank, file);
+
+            let ch = match (self.piece_at(square), self.color_at(square)) {
+                (Some(piece), Some(Color::White)) => char::from(piece).to_ascii_uppercase(),
+                (Some(piece), _) => char::from(piece),
+                _ => '.',
+            };
+
+            row.push(' ');
+            row.push(ch);
+        }
+
+        row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{piece::Piece, r#move::Move};
+
+    use super::*;
+
+    #[test]
+    fn test_pretty() {
+        let board = Board::default();
+        let pretty = board.pretty();
+        let lines = pretty.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines[0], "8 r n b q k b n r");
+        assert_eq!(lines[4], "4 . . . . . . . .");
+        assert_eq!(lines[7], "1 R N B Q K B N R");
+        assert_eq!(lines[8], "  a b c d e f g h");
+    }
+
+    #[test]
+    fn test_diff() {
+        let board = Board::default();
+        let mut other = board;
+        *other.piece_bitboard_mut(Piece::Knight) ^= Square::G1.bitboard();
+        *other.color_bitboard_mut(Color::White) ^= Square::G1.bitboard();
+
+        let diff = board.diff(&other);
+        let lines = diff.lines().collect::<Vec<&str>>();
+
+        assert_eq!(
+            lines[7],
+            "1 R N B Q K B N R   1 R N B Q K B . R   1 . . . . . . x ."
+        );
+        assert_eq!(diff.matches('x').count(), 1);
+
+        // A move changes both squares
+        let other = board.make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.diff(&other).matches('x').count(), 2);
+        assert_eq!(board.diff(&board).matches('x').count(), 0);
+    }
+}
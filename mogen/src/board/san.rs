@@ -0,0 +1,465 @@
+use crate::{
+    pgn::{movetext_tokens, RESULTS},
+    MoveGen,
+};
+
+use super::{piece::Piece, r#move::Move, square::Square, Board};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseSanError;
+
+// The first SAN move of a line that couldn't be played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IllegalSanMove(pub String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UciToSanError {
+    BadUci,
+    IllegalMove,
+}
+
+impl Board {
+    // Standard algebraic notation for a legal move, e.g. "Nbd2", "exd5", "e8=Q+"
+    pub fn san(&self, mv: Move, move_gen: &MoveGen) -> String {
+        let moves = self.legal_moves_for_san(move_gen);
+        let mut san = self.san_without_suffix(mv, &moves);
+
+        let after = self.make_move(mv);
+        if after.is_in_check(after.active_color, move_gen.smg()) {
+            if move_gen.has_any_legal_move(&after) {
+                san.push('+');
+            } else {
+                san.push('#');
+            }
+        }
+
+        san
+    }
+
+    // Check/mate suffixes, annotations ("!", "?") and "=" are optional in `san`,
+    // and so is "x". Extra disambiguation ("Ngf3", "Ng1f3") is accepted, but
+    // a move that matches more than one legal move isn't.
+    pub fn parse_san(&self, san: &str, move_gen: &MoveGen) -> Result<Move, ParseSanError> {
+        let san = normalize_san(san);
+        let moves = self.legal_moves_for_san(move_gen);
+
+        if san == "O-O" || san == "O-O-O" {
+            let file = if san == "O-O" { 6 } else { 2 };
+
+            return moves
+                .into_iter()
+                .find(|mv| self.castling_rook_move(*mv).is_some() && mv.target() as u8 % 8 == file)
+                .ok_or(ParseSanError);
+        }
+
+        let san = SanMove::parse(&san).ok_or(ParseSanError)?;
+        let mut matching = moves.into_iter().filter(|mv| san.matches(self, *mv));
+
+        match (matching.next(), matching.next()) {
+            (Some(mv), None) => Ok(mv),
+            _ => Err(ParseSanError),
+        }
+    }
+
+    // Plays a single line of movetext such as "1.e4 e5 2.Nf3 Nc6", ignoring
+    // move numbers, comments, annotations and game results
+    pub fn apply_san_line(&self, line: &str, move_gen: &MoveGen) -> Result<Board, IllegalSanMove> {
+        let mut board = *self;
+
+        for token in movetext_tokens(line) {
+            if RESULTS.contains(&token.as_str()) {
+                continue;
+            }
+
+            let mv = board
+                .parse_san(&token, move_gen)
+                .map_err(|_| IllegalSanMove(token))?;
+            board = board.make_move(mv);
+        }
+
+        Ok(board)
+    }
+
+    pub fn uci_to_san(&self, uci: &str, move_gen: &MoveGen) -> Result<String, UciToSanError> {
+        let mv = Move::try_from(uci).map_err(|_| UciToSanError::BadUci)?;
+
+        if !self.legal_moves_for_san(move_gen).contains(&mv) {
+            return Err(UciToSanError::IllegalMove);
+        }
+
+        Ok(self.san(mv, move_gen))
+    }
+
+    // UCI with the moving piece's letter in front and "x" before a captured
+    // square, e.g. "Ng1f3" or "e4xd5". Cheaper than SAN, as nothing is disambiguated.
+    pub fn move_label(&self, mv: Move) -> String {
+        let mut label = String::new();
+
+        if let Some(piece) = self
+            .piece_at(mv.source())
+            .filter(|piece| *piece != Piece::Pawn)
+        {
+            label.push(char::from(piece).to_ascii_uppercase());
+        }
+
+        let uci = mv.to_string();
+        let (source, rest) = uci.split_at(2);
+        label.push_str(source);
+        if self.is_capture(mv) {
+            label.push('x');
+        }
+        label.push_str(rest);
+
+        label
+    }
+
+    fn legal_moves_for_san(&self, move_gen: &MoveGen) -> Vec<Move> {
+        let mut moves = Vec::new();
+        move_gen.legal_moves(self, &mut moves);
+        moves
+    }
+
+    // `moves` are the legal moves, used to disambiguate between pieces
+    fn san_without_suffix(&self, mv: Move, moves: &[Move]) -> String {
+        let source = mv.source();
+        let target = mv.target();
+
+        let Some(piece) = self.piece_at(source) else {
+            return mv.to_string();
+        };
+
+        let mut san = String::new();
+
+        if piece == Piece::King && (source as u8 % 8).abs_diff(target as u8 % 8) == 2 {
+            san.push_str(if (target as u8 % 8) == 6 {
+                "O-O"
+            } else {
+                "O-O-O"
+            });
+            return san;
+        }
+
+        let is_capture = self.is_capture(mv);
+
+        if piece == Piece::Pawn {
+            if is_capture {
+                san.push(file_char(source));
+            }
+        } else {
+            san.push(char::from(piece).to_ascii_uppercase());
+
+            // Other pieces of the same type that can legally reach the target
+            let others = moves
+                .iter()
+                .filter(|other| {
+                    other.target() == target
+                        && other.source() != source
+                        && self.piece_at(other.source()) == Some(piece)
+                })
+                .map(|other| other.source())
+                .collect::<Vec<Square>>();
+
+            if !others.is_empty() {
+                let same_file = others.iter().any(|sq| *sq as u8 % 8 == source as u8 % 8);
+                let same_rank = others.iter().any(|sq| *sq as u8 / 8 == source as u8 / 8);
+
+                if !same_file {
+                    san.push(file_char(source));
+                } else if !same_rank {
+                    san.push(rank_char(source));
+                } else {
+                    san.push(file_char(source));
+                    san.push(rank_char(source));
+                }
+            }
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+
+        san.push_str(&target.to_string());
+
+        if let Some(promotion) = mv.promotion() {
+            san.push('=');
+            san.push(char::from(promotion).to_ascii_uppercase());
+        }
+
+        san
+    }
+}
+
+// A non-castling SAN move, split into the parts a legal move must match
+struct SanMove {
+    piece: Piece,
+    file: Option<u8>,
+    rank: Option<u8>,
+    target: Square,
+    promotion: Option<Piece>,
+}
+
+impl SanMove {
+    // Expects `san` to have gone through `normalize_san`
+    fn parse(san: &str) -> Option<SanMove> {
+        let mut chars = san.chars().filter(|ch| *ch != 'x').collect::<Vec<char>>();
+
+        // Piece letters are uppercase, so "b" is always a file
+        let piece = match chars.first() {
+            Some(ch) if ch.is_ascii_uppercase() => match Piece::try_from(chars.remove(0)) {
+                Ok(Piece::Pawn) | Err(_) => return None,
+                Ok(piece) => piece,
+            },
+            _ => Piece::Pawn,
+        };
+
+        let promotion = match chars.last() {
+            Some(ch) if ch.is_ascii_alphabetic() => match Piece::try_from(chars.pop()?) {
+                Ok(Piece::Pawn | Piece::King) | Err(_) => return None,
+                Ok(piece) => Some(piece),
+            },
+            _ => None,
+        };
+
+        if chars.len() < 2 || chars.len() > 4 {
+            return None;
+        }
+
+        let target = chars
+            .split_off(chars.len() - 2)
+            .into_iter()
+            .collect::<String>();
+        let target = Square::try_from(target.as_str()).ok()?;
+
+        let mut file = None;
+        let mut rank = None;
+        for ch in chars {
+            match ch {
+                'a'..='h' if file.is_none() && rank.is_none() => file = Some(ch as u8 - b'a'),
+                '1'..='8' if rank.is_none() => rank = Some(ch as u8 - b'1'),
+                _ => return None,
+            }
+        }
+
+        Some(SanMove {
+            piece,
+            file,
+            rank,
+            target,
+            promotion,
+        })
+    }
+
+    fn matches(&self, board: &Board, mv: Move) -> bool {
+        let source = mv.source() as u8;
+
+        board.piece_at(mv.source()) == Some(self.piece)
+            && mv.target() == self.target
+            && mv.promotion() == self.promotion
+            && self.file.is_none_or(|file| source % 8 == file)
+            && self.rank.is_none_or(|rank| source / 8 == rank)
+    }
+}
+
+fn file_char(square: Square) -> char {
+    (square as u8 % 8 + b'a') as char
+}
+
+fn rank_char(square: Square) -> char {
+    (square as u8 / 8 + b'1') as char
+}
+
+fn normalize_san(san: &str) -> String {
+    san.trim()
+        .trim_end_matches(['+', '#', '!', '?'])
+        .replace("0-0-0", "O-O-O")
+        .replace("0-0", "O-O")
+        .replace('=', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_san() {
+        let move_gen = MoveGen::new();
+        let board = Board::default();
+
+        assert_eq!(
+            board.san(Move::new(Square::G1, Square::F3, None), &move_gen),
+            "Nf3"
+        );
+        assert_eq!(
+            board.san(Move::new(Square::E2, Square::E4, None), &move_gen),
+            "e4"
+        );
+
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/6K1/R6R w - - 0 1").unwrap();
+        assert_eq!(
+            board.san(Move::new(Square::E4, Square::D5, None), &move_gen),
+            "exd5"
+        );
+        assert_eq!(
+            board.san(Move::new(Square::A1, Square::D1, None), &move_gen),
+            "Rad1"
+        );
+        assert_eq!(
+            board.san(Move::new(Square::H1, Square::H8, None), &move_gen),
+            "Rh8+"
+        );
+
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/1P6/R5K1 w - - 0 1").unwrap();
+        assert_eq!(
+            board.san(Move::new(Square::A1, Square::A8, None), &move_gen),
+            "Ra8#"
+        );
+
+        let board = Board::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.san(
+                Move::new(Square::E7, Square::E8, Some(Piece::Queen)),
+                &move_gen
+            ),
+            "e8=Q+"
+        );
+    }
+
+    #[test]
+    fn test_parse_san() {
+        let move_gen = MoveGen::new();
+        let board = Board::default();
+
+        assert_eq!(
+            board.parse_san("Nf3", &move_gen),
+            Ok(Move::new(Square::G1, Square::F3, None))
+        );
+        assert_eq!(
+            board.parse_san("e4!", &move_gen),
+            Ok(Move::new(Square::E2, Square::E4, None))
+        );
+        assert_eq!(board.parse_san("e5", &move_gen), Err(ParseSanError));
+        assert_eq!(board.parse_san("Nd2", &move_gen), Err(ParseSanError));
+
+        let board = Board::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.parse_san("e8Q+", &move_gen),
+            Ok(Move::new(Square::E7, Square::E8, Some(Piece::Queen)))
+        );
+        assert_eq!(
+            board.parse_san("e8=N", &move_gen),
+            Ok(Move::new(Square::E7, Square::E8, Some(Piece::Knight)))
+        );
+        assert_eq!(board.parse_san("e8", &move_gen), Err(ParseSanError));
+
+        // Over-disambiguated and capture-less forms still identify the move
+        let board = Board::default();
+        let nf3 = Ok(Move::new(Square::G1, Square::F3, None));
+        assert_eq!(board.parse_san("Ngf3", &move_gen), nf3);
+        assert_eq!(board.parse_san("N1f3", &move_gen), nf3);
+        assert_eq!(board.parse_san("Ng1f3", &move_gen), nf3);
+        assert_eq!(board.parse_san("Nbf3", &move_gen), Err(ParseSanError));
+
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/6K1/R6R w - - 0 1").unwrap();
+        assert_eq!(
+            board.parse_san("ed5", &move_gen),
+            Ok(Move::new(Square::E4, Square::D5, None))
+        );
+        assert_eq!(
+            board.parse_san("Rad1", &move_gen),
+            Ok(Move::new(Square::A1, Square::D1, None))
+        );
+
+        // Both rooks can reach d1
+        assert_eq!(board.parse_san("Rd1", &move_gen), Err(ParseSanError));
+
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(
+            board.parse_san("O-O-O", &move_gen),
+            Ok(Move::new(Square::E1, Square::C1, None))
+        );
+        assert_eq!(
+            board.parse_san("0-0", &move_gen),
+            Ok(Move::new(Square::E1, Square::G1, None))
+        );
+    }
+
+    #[test]
+    fn test_move_label() {
+        let board = Board::default();
+
+        assert_eq!(
+            board.move_label(Move::new(Square::G1, Square::F3, None)),
+            "Ng1f3"
+        );
+        assert_eq!(
+            board.move_label(Move::new(Square::E2, Square::E4, None)),
+            "e2e4"
+        );
+
+        let board = Board::from_fen("1n2k3/P7/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.move_label(Move::new(Square::E4, Square::D5, None)),
+            "e4xd5"
+        );
+        assert_eq!(
+            board.move_label(Move::new(Square::E1, Square::E2, None)),
+            "Ke1e2"
+        );
+        assert_eq!(
+            board.move_label(Move::new(Square::A7, Square::B8, Some(Piece::Queen))),
+            "a7xb8q"
+        );
+    }
+
+    #[test]
+    fn test_apply_san_line() {
+        let move_gen = MoveGen::new();
+        let board = Board::default();
+
+        let italian = board
+            .apply_san_line("1.e4 e5 2.Nf3 Nc6 3.Bc4", &move_gen)
+            .unwrap();
+        assert_eq!(
+            italian.fen().split(' ').take(2).collect::<Vec<_>>(),
+            ["r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R", "b"]
+        );
+
+        assert_eq!(
+            board.apply_san_line("1. e4 e5 2. Nf3 Nc6", &move_gen),
+            board.apply_san_line("e4 e5 Nf3 Nc6", &move_gen)
+        );
+        let e4_e5 = board.apply_san_line("1.e4 e5", &move_gen);
+        for result in ["1-0", "0-1", "1/2-1/2", "*"] {
+            assert_eq!(
+                board.apply_san_line(&format!("1.e4 e5 {result}"), &move_gen),
+                e4_e5
+            );
+        }
+
+        assert_eq!(
+            board.apply_san_line("1.e4 e5 2.Ke3", &move_gen),
+            Err(IllegalSanMove("Ke3".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_uci_to_san() {
+        let move_gen = MoveGen::new();
+        let board = Board::default();
+
+        assert_eq!(board.uci_to_san("g1f3", &move_gen), Ok("Nf3".to_string()));
+        assert_eq!(board.uci_to_san("e2e4", &move_gen), Ok("e4".to_string()));
+        assert_eq!(
+            board.uci_to_san("e2e5", &move_gen),
+            Err(UciToSanError::IllegalMove)
+        );
+        assert_eq!(
+            board.uci_to_san("e7e5", &move_gen),
+            Err(UciToSanError::IllegalMove)
+        );
+        assert_eq!(
+            board.uci_to_san("nf3", &move_gen),
+            Err(UciToSanError::BadUci)
+        );
+    }
+}
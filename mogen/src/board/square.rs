@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::r#static::generation::coords;
+use crate::r#static::{generation::coords, move_masks::KING_MOVE_MASKS};
 
-use super::bitboard::Bitboard;
+use super::{bitboard::Bitboard, file::File, rank::Rank};
 
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -148,6 +148,77 @@ impl Square {
     pub fn from_coords(rank: u8, file: u8) -> Self {
         Square::ALL[(rank * 8 + file) as usize]
     }
+
+    pub fn new(file: File, rank: Rank) -> Self {
+        Square::from_coords(rank as u8, file as u8)
+    }
+
+    // Squares stepping `dr` ranks and `df` files at a time, excluding `self`,
+    // until falling off the board
+    pub fn ray(self, dr: i8, df: i8) -> Ray {
+        let (rank, file) = coords(self as u8);
+        Ray {
+            rank: rank as i8,
+            file: file as i8,
+            dr,
+            df,
+        }
+    }
+
+    // Unit (rank, file) step from `self` towards `other`, if they share a line
+    pub fn direction_to(self, other: Square) -> Option<(i8, i8)> {
+        let (rank, file) = coords(self as u8);
+        let (other_rank, other_file) = coords(other as u8);
+
+        let dr = other_rank as i8 - rank as i8;
+        let df = other_file as i8 - file as i8;
+
+        if self == other || (dr != 0 && df != 0 && dr.abs() != df.abs()) {
+            return None;
+        }
+
+        Some((dr.signum(), df.signum()))
+    }
+
+    // Squares a king on `self` could step to
+    pub fn neighbors(&self) -> impl Iterator<Item = Square> {
+        let mut mask = KING_MOVE_MASKS[*self as usize];
+        std::iter::from_fn(move || (!mask.is_empty()).then(|| Square::ALL[mask.pop_lsb()]))
+    }
+
+    pub fn file(&self) -> File {
+        File::from(*self)
+    }
+
+    pub fn rank(&self) -> Rank {
+        Rank::from(*self)
+    }
+}
+
+pub struct Ray {
+    rank: i8,
+    file: i8,
+    dr: i8,
+    df: i8,
+}
+
+impl Iterator for Ray {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dr == 0 && self.df == 0 {
+            return None;
+        }
+
+        self.rank += self.dr;
+        self.file += self.df;
+
+        if !(0..8).contains(&self.rank) || !(0..8).contains(&self.file) {
+            return None;
+        }
+
+        Some(Square::from_coords(self.rank as u8, self.file as u8))
+    }
 }
 
 impl Display for Square {
@@ -325,6 +396,59 @@ mod tests {
         assert_eq!(format!("{}", Square::H8), "h8");
     }
 
+    #[test]
+    fn test_file_rank_round_trip() {
+        for square in Square::ALL {
+            assert_eq!(Square::new(square.file(), square.rank()), square);
+        }
+
+        assert_eq!(Square::new(File::E, Rank::Fourth), Square::E4);
+        assert_eq!(Square::H8.file(), File::H);
+        assert_eq!(Square::H8.rank(), Rank::Eighth);
+        assert_eq!(format!("{}{}", File::C, Rank::Sixth), "c6");
+    }
+
+    #[test]
+    fn test_ray() {
+        assert_eq!(
+            Square::A1.ray(1, 1).collect::<Vec<Square>>(),
+            vec![
+                Square::B2,
+                Square::C3,
+                Square::D4,
+                Square::E5,
+                Square::F6,
+                Square::G7,
+                Square::H8
+            ]
+        );
+        assert_eq!(
+            Square::E4.ray(0, -1).collect::<Vec<Square>>(),
+            vec![Square::D4, Square::C4, Square::B4, Square::A4]
+        );
+        assert_eq!(Square::H8.ray(1, 0).count(), 0);
+        assert_eq!(Square::E4.ray(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(
+            Square::A1.neighbors().collect::<Vec<Square>>(),
+            vec![Square::B1, Square::A2, Square::B2]
+        );
+        assert_eq!(Square::E4.neighbors().count(), 8);
+        assert_eq!(Square::H5.neighbors().count(), 5);
+    }
+
+    #[test]
+    fn test_direction_to() {
+        assert_eq!(Square::E1.direction_to(Square::E8), Some((1, 0)));
+        assert_eq!(Square::E4.direction_to(Square::B1), Some((-1, -1)));
+        assert_eq!(Square::H1.direction_to(Square::A8), Some((1, -1)));
+        assert_eq!(Square::E1.direction_to(Square::F3), None);
+        assert_eq!(Square::E1.direction_to(Square::E1), None);
+    }
+
     #[test]
     fn test_square_from_coords() {
         let squares = [Square::E7, Square::A2, Square::C8, Square::H1, Square::F6];
@@ -0,0 +1,69 @@
+use crate::MoveGen;
+
+use super::{color::Color, draw::DrawReason, Board};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Checkmate(Color), // The winner
+    Stalemate,
+    Draw(DrawReason),
+    Check,
+    Ongoing,
+}
+
+impl Board {
+    // Checkmate and stalemate come first, as they end the game even when the
+    // seventy-five move rule would also apply
+    pub fn status(&self, move_gen: &MoveGen) -> Status {
+        let in_check = self.is_in_check(self.active_color, move_gen.smg());
+
+        if !move_gen.has_any_legal_move(self) {
+            if in_check {
+                return Status::Checkmate(self.active_color.inverse());
+            }
+            return Status::Stalemate;
+        }
+
+        if let Some(reason) = self.draw_by_rule() {
+            return Status::Draw(reason);
+        }
+
+        if in_check {
+            Status::Check
+        } else {
+            Status::Ongoing
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let move_gen = MoveGen::new();
+
+        assert_eq!(Board::default().status(&move_gen), Status::Ongoing);
+
+        // Back rank mate delivered by white
+        let mated = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert_eq!(mated.status(&move_gen), Status::Checkmate(Color::White));
+
+        let check = Board::from_fen("R5k1/5pp1/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert_eq!(check.status(&move_gen), Status::Check);
+
+        let stalemated = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(stalemated.status(&move_gen), Status::Stalemate);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 150 120").unwrap();
+        assert_eq!(
+            board.status(&move_gen),
+            Status::Draw(DrawReason::SeventyFiveMoveRule)
+        );
+
+        // Mate on the move that reaches the seventy-five move limit still counts
+        let mated = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 150 120").unwrap();
+        assert_eq!(mated.status(&move_gen), Status::Checkmate(Color::White));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::magic::SlidingMoveGen;
+
+use super::{bitboard::Bitboard, color::Color, piece::Piece, square::Square, Board};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidPosition {
+    KingCount(Color),
+    OpponentInCheck,
+    PawnOnBackRank,
+    BadCastlingRights,
+    BadEnPassant,
+}
+
+impl Board {
+    // Checks a parsed or hand-built position could occur in a real game
+    pub fn is_valid(&self, smg: &SlidingMoveGen) -> Result<(), InvalidPosition> {
+        for color in [Color::White, Color::Black] {
+            if self.piece_count(Piece::King, color) != 1 {
+                return Err(InvalidPosition::KingCount(color));
+            }
+        }
+
+        if self.is_in_check(self.active_color.inverse(), smg) {
+            return Err(InvalidPosition::OpponentInCheck);
+        }
+
+        let back_ranks = Bitboard::RANK_1 | Bitboard::RANK_8;
+        if !(self.piece_bitboard(Piece::Pawn) & back_ranks).is_empty() {
+            return Err(InvalidPosition::PawnOnBackRank);
+        }
+
+        if !self.castling_rights_match_pieces() {
+            return Err(InvalidPosition::BadCastlingRights);
+        }
+
+        if !self.en_passant_is_plausible() {
+            return Err(InvalidPosition::BadEnPassant);
+        }
+
+        Ok(())
+    }
+
+    // Each castling right needs the king and that rook on their starting squares
+    fn castling_rights_match_pieces(&self) -> bool {
+        let sides = [
+            (Color::White, Square::E1, Square::H1, Square::A1),
+            (Color::Black, Square::E8, Square::H8, Square::A8),
+        ];
+
+        sides.into_iter().all(|(color, king, kingside, queenside)| {
+            let has = |piece, square: Square| self.bitboard(piece, color) & square.bitboard();
+            let king_home = !has(Piece::King, king).is_empty();
+
+            (!self.flags.kingside(color) || (king_home && !has(Piece::Rook, kingside).is_empty()))
+                && (!self.flags.queenside(color)
+                    || (king_home && !has(Piece::Rook, queenside).is_empty()))
+        })
+    }
+
+    // The en passant square and the one behind it must be empty, with the
+    // pawn that just double pushed in front of it
+    fn en_passant_is_plausible(&self) -> bool {
+        let Some(square) = self.en_passant_square() else {
+            return true;
+        };
+
+        let (behind, pushed) = match self.active_color {
+            Color::White => (square.bitboard().north(), square.bitboard().south()),
+            Color::Black => (square.bitboard().south(), square.bitboard().north()),
+        };
+        let pawns = self.bitboard(Piece::Pawn, self.active_color.inverse());
+
+        ((square.bitboard() | behind) & self.all_pieces()).is_empty()
+            && !(pushed & pawns).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::r#move::Move;
+
+    #[test]
+    fn test_is_valid() {
+        let smg = SlidingMoveGen::new();
+
+        assert_eq!(Board::default().is_valid(&smg), Ok(()));
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.add_piece(Piece::King, Color::White, Square::A1);
+        assert_eq!(
+            board.is_valid(&smg),
+            Err(InvalidPosition::KingCount(Color::White))
+        );
+
+        let board = Board::from_position_fen("8/8/8/8/8/8/8/4K3").unwrap();
+        assert_eq!(
+            board.is_valid(&smg),
+            Err(InvalidPosition::KingCount(Color::Black))
+        );
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.add_piece(Piece::Rook, Color::White, Square::E4);
+        assert_eq!(board.is_valid(&smg), Err(InvalidPosition::OpponentInCheck));
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/P3K3 w - - 0 1").unwrap();
+        assert_eq!(board.is_valid(&smg), Err(InvalidPosition::PawnOnBackRank));
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w K - 0 1").unwrap();
+        assert_eq!(
+            board.is_valid(&smg),
+            Err(InvalidPosition::BadCastlingRights)
+        );
+
+        let board = Board::from_fen("r3k3/8/8/8/8/8/8/4K3 w q - 0 1").unwrap();
+        assert_eq!(board.is_valid(&smg), Ok(()));
+
+        let board = Board::default().make_move(Move::new(Square::E2, Square::E4, None));
+        assert_eq!(board.is_valid(&smg), Ok(()));
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        board.set_en_passant(Some(Square::E6)).unwrap();
+        assert_eq!(board.is_valid(&smg), Err(InvalidPosition::BadEnPassant));
+    }
+
+    #[test]
+    fn test_is_valid_opponent_in_check() {
+        let smg = SlidingMoveGen::new();
+        let validate = |fen| Board::from_fen(fen).unwrap().is_valid(&smg);
+
+        // White to move, but the black king is attacked
+        for fen in [
+            "4k3/8/8/8/8/8/8/4RK2 w - - 0 1",
+            "4k3/8/5N2/8/8/8/8/5K2 w - - 0 1",
+            "4k3/3P4/8/8/8/8/8/5K2 w - - 0 1",
+        ] {
+            assert_eq!(validate(fen), Err(InvalidPosition::OpponentInCheck));
+        }
+
+        // The side to move may be in check, and blocked lines don't count
+        assert_eq!(validate("4k3/8/8/8/8/8/8/4RK2 b - - 0 1"), Ok(()));
+        assert_eq!(validate("4k3/4p3/8/8/8/8/8/4RK2 w - - 0 1"), Ok(()));
+        assert_eq!(validate("4k3/3p4/8/8/B7/8/8/5K2 w - - 0 1"), Ok(()));
+    }
+}
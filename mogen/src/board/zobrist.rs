@@ -0,0 +1,253 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    color::Color, flags::Flags, pawn_attack_sources, piece::Piece, r#move::Move, square::Square,
+    Board,
+};
+
+#[derive(Debug, Clone)]
+pub struct ZobristKeys {
+    pieces: [[[u64; 64]; 6]; 2],
+    black_to_move: u64,
+    castling: [u64; 16],
+    en_passant_file: [u64; 8],
+}
+
+impl ZobristKeys {
+    // Fixed seed, so hashes are stable between runs
+    pub const DEFAULT_SEED: u64 = 0x6d6f67656e;
+
+    pub fn new() -> Self {
+        Self::with_seed(Self::DEFAULT_SEED)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut pieces = [[[0; 64]; 6]; 2];
+        for color_keys in pieces.iter_mut() {
+            for piece_keys in color_keys.iter_mut() {
+                for key in piece_keys.iter_mut() {
+                    *key = rng.gen();
+                }
+            }
+        }
+
+        Self {
+            pieces,
+            black_to_move: rng.gen(),
+            castling: rng.gen(),
+            en_passant_file: rng.gen(),
+        }
+    }
+
+    pub fn piece(&self, piece: Piece, color: Color, square: usize) -> u64 {
+        self.pieces[color as usize][piece as usize][square]
+    }
+
+    pub fn black_to_move(&self) -> u64 {
+        self.black_to_move
+    }
+
+    pub fn castling(&self, rights: u8) -> u64 {
+        self.castling[rights as usize & 0b1111]
+    }
+
+    pub fn en_passant_file(&self, file: u8) -> u64 {
+        self.en_passant_file[file as usize]
+    }
+}
+
+impl Default for ZobristKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Board {
+    // The en passant file only contributes when a capture is actually possible,
+    // so unusable en passant squares don't affect position identity
+    pub fn zobrist_hash(&self, keys: &ZobristKeys) -> u64 {
+        let mut hash = 0;
+
+        for color in [Color::White, Color::Black] {
+            for piece in Piece::ALL {
+                let mut bitboard = self.bitboard(piece, color);
+                while !bitboard.is_empty() {
+                    hash ^= keys.piece(piece, color, bitboard.pop_lsb());
+                }
+            }
+        }
+
+        if self.active_color == Color::Black {
+            hash ^= keys.black_to_move();
+        }
+
+        hash ^= keys.castling(*self.flags);
+
+        if self.can_capture_en_passant() {
+            hash ^= keys.en_passant_file(self.flags.en_passant_file());
+        }
+
+        hash
+    }
+
+    // The hash of `make_move(mv)`, updated from this position's hash rather
+    // than rebuilt from a copy of the board, so `hash` must be this board's
+    // `zobrist_hash`
+    pub fn key_after(&self, hash: u64, mv: Move, keys: &ZobristKeys) -> u64 {
+        let from = mv.source();
+        let to = mv.target();
+        let color = self.active_color;
+
+        let Some(piece) = self.piece_at(from) else {
+            return self.make_move(mv).zobrist_hash(keys);
+        };
+
+        let mut hash = hash ^ keys.piece(piece, color, from as usize);
+        hash ^= keys.piece(mv.promotion().unwrap_or(piece), color, to as usize);
+
+        if let Some(captured) = self.piece_at(to) {
+            hash ^= keys.piece(captured, color.inverse(), to as usize);
+        } else if self.is_en_passant(mv) {
+            let captured = match color {
+                Color::White => to.bitboard().south(),
+                Color::Black => to.bitboard().north(),
+            };
+            hash ^= keys.piece(
+                Piece::Pawn,
+                color.inverse(),
+                captured.trailing_zeros() as usize,
+            );
+        }
+
+        if let Some((rook_from, rook_to)) = self.castling_rook_move(mv) {
+            hash ^= keys.piece(Piece::Rook, color, rook_from as usize);
+            hash ^= keys.piece(Piece::Rook, color, rook_to as usize);
+        }
+
+        hash ^= keys.black_to_move();
+
+        let rights = *self.flags & Flags::CASTLING_MASK;
+        hash ^= keys.castling(rights) ^ keys.castling(rights & !self.castling_rights_lost(mv));
+
+        if self.can_capture_en_passant() {
+            hash ^= keys.en_passant_file(self.flags.en_passant_file());
+        }
+
+        // A double push only counts if an enemy pawn can take en passant
+        if piece == Piece::Pawn && (from as u8).abs_diff(to as u8) == 16 {
+            let square = Square::ALL[(from as usize + to as usize) / 2];
+            let enemy_pawns = self.bitboard(Piece::Pawn, color.inverse());
+
+            if !(pawn_attack_sources(square, color.inverse()) & enemy_pawns).is_empty() {
+                hash ^= keys.en_passant_file(from as u8 % 8);
+            }
+        }
+
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{r#move::Move, square::Square};
+
+    use super::*;
+
+    #[test]
+    fn test_zobrist_hash() {
+        let keys = ZobristKeys::new();
+        let board = Board::default();
+
+        assert_eq!(
+            board.zobrist_hash(&keys),
+            Board::default().zobrist_hash(&keys)
+        );
+
+        let after = board.make_move(Move::new(Square::G1, Square::F3, None));
+        assert_ne!(board.zobrist_hash(&keys), after.zobrist_hash(&keys));
+
+        let mut black_to_move = board;
+        black_to_move.active_color = Color::Black;
+        assert_ne!(board.zobrist_hash(&keys), black_to_move.zobrist_hash(&keys));
+
+        let mut no_castling = board;
+        no_castling.flags.0 = 0;
+        assert_ne!(board.zobrist_hash(&keys), no_castling.zobrist_hash(&keys));
+    }
+
+    #[test]
+    fn test_key_after() {
+        let keys = ZobristKeys::new();
+        let cases = [
+            // Double push nobody can take en passant, and a rook capturing a rook
+            ("r3k2r/8/8/8/8/8/4P3/R3K2R w KQkq - 0 1", "e2e4"),
+            ("r3k2r/8/8/8/8/8/4P3/R3K2R w KQkq - 0 1", "a1a8"),
+            // Capture and promotion with capture
+            ("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5"),
+            ("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8q"),
+            // Castling
+            ("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1"),
+            ("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8c8"),
+            // Double push allowing en passant, then en passant itself
+            ("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1", "e2e4"),
+        ];
+
+        for (fen, uci) in cases {
+            let board = Board::from_fen(fen).unwrap();
+            let mv = Move::try_from(uci).unwrap();
+
+            assert_eq!(
+                board.key_after(board.zobrist_hash(&keys), mv, &keys),
+                board.make_move(mv).zobrist_hash(&keys),
+                "{fen} {uci}"
+            );
+        }
+
+        let board = Board::from_fen("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1")
+            .unwrap()
+            .make_move(Move::new(Square::E2, Square::E4, None));
+        let mv = Move::new(Square::D4, Square::E3, None);
+        assert!(board.is_en_passant(mv));
+        assert_eq!(
+            board.key_after(board.zobrist_hash(&keys), mv, &keys),
+            board.make_move(mv).zobrist_hash(&keys)
+        );
+
+        // Chaining keys through a game never needs a full rebuild
+        let mut board = Board::default();
+        let mut hash = board.zobrist_hash(&keys);
+        for uci in [
+            "e2e4", "d7d5", "e4d5", "g8f6", "f1b5", "c7c6", "g1f3", "c6b5", "e1g1",
+        ] {
+            let mv = Move::try_from(uci).unwrap();
+            hash = board.key_after(hash, mv, &keys);
+            board = board.make_move(mv);
+            assert_eq!(hash, board.zobrist_hash(&keys), "{uci}");
+        }
+    }
+
+    #[test]
+    fn test_zobrist_hash_en_passant() {
+        let keys = ZobristKeys::new();
+
+        // No black pawn next to e4, so the en passant square is unusable
+        let board = Board::default().make_move(Move::new(Square::E2, Square::E4, None));
+        let mut without_ep = board;
+        without_ep.clear_en_passant();
+
+        assert!(board.flags.can_en_passant());
+        assert_eq!(board.zobrist_hash(&keys), without_ep.zobrist_hash(&keys));
+
+        // The pawn on d4 can capture en passant
+        let mut board = Board::new();
+        board.add_piece(Piece::Pawn, Color::White, Square::E2);
+        board.add_piece(Piece::Pawn, Color::Black, Square::D4);
+        let board = board.make_move(Move::new(Square::E2, Square::E4, None));
+        let mut without_ep = board;
+        without_ep.clear_en_passant();
+
+        assert_ne!(board.zobrist_hash(&keys), without_ep.zobrist_hash(&keys));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    board::{
+        draw::{DrawClaim, DrawReason},
+        r#move::Move,
+        san::ParseSanError,
+        Board,
+    },
+    MoveGen,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    tags: Vec<(String, String)>,
+    start: Board,
+    board: Board,
+    moves: Vec<Move>,
+    // Position before each move in `moves`
+    history: Vec<Board>,
+}
+
+impl Game {
+    pub fn new(start: Board) -> Game {
+        Game {
+            tags: Vec::new(),
+            start,
+            board: start,
+            moves: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    // PGN tag pairs in insertion order
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_owned(),
+            None => self.tags.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    // Assumes `mv` is legal in the current position
+    pub fn push(&mut self, mv: Move) {
+        let next = self.board.make_move(mv);
+        self.history.push(std::mem::replace(&mut self.board, next));
+        self.moves.push(mv);
+    }
+
+    pub fn push_san(&mut self, san: &str, move_gen: &MoveGen) -> Result<Move, ParseSanError> {
+        let mv = self.board.parse_san(san, move_gen)?;
+        self.push(mv);
+        Ok(mv)
+    }
+
+    // Undoes the last move, returning it
+    pub fn takeback(&mut self) -> Option<Move> {
+        let board = self.history.pop()?;
+        self.board = board;
+        self.moves.pop()
+    }
+
+    // Times the current position has occurred, including now. An en passant
+    // square only sets a position apart if a capture there is possible.
+    pub fn repetitions(&self) -> usize {
+        let current = repetition_key(&self.board);
+
+        1 + self
+            .history
+            .iter()
+            .filter(|board| repetition_key(board).same_position(&current))
+            .count()
+    }
+
+    // Like `Board::is_draw`, but also ends the game on a fivefold repetition
+    pub fn is_draw(&self, move_gen: &MoveGen) -> Option<DrawReason> {
+        let reason = self.draw_by_rule()?;
+        move_gen.has_any_legal_move(&self.board).then_some(reason)
+    }
+
+    // Like `Board::can_claim_draw`, but also allows claiming a threefold repetition
+    pub fn can_claim_draw(&self, move_gen: &MoveGen) -> Option<DrawClaim> {
+        if self.draw_by_rule().is_some() {
+            return None;
+        }
+
+        let claim = if self.repetitions() >= 3 {
+            DrawClaim::ThreefoldRepetition
+        } else if self.board.halfmoves >= 100 {
+            DrawClaim::FiftyMoveRule
+        } else {
+            return None;
+        };
+
+        move_gen.has_any_legal_move(&self.board).then_some(claim)
+    }
+
+    fn draw_by_rule(&self) -> Option<DrawReason> {
+        self.board
+            .draw_by_rule()
+            .or_else(|| (self.repetitions() >= 5).then_some(DrawReason::FivefoldRepetition))
+    }
+}
+
+fn repetition_key(board: &Board) -> Board {
+    let mut board = *board;
+    if !board.can_capture_en_passant() {
+        board.clear_en_passant();
+    }
+    board
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(Board::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::square::Square;
+
+    use super::*;
+
+    #[test]
+    fn test_push_san_takeback() {
+        let move_gen = MoveGen::new();
+        let mut game = Game::default();
+
+        game.push_san("e4", &move_gen).unwrap();
+        game.push_san("e5", &move_gen).unwrap();
+        game.push_san("Nf3", &move_gen).unwrap();
+
+        assert_eq!(game.moves().len(), 3);
+        assert_eq!(game.push_san("Nf3", &move_gen), Err(ParseSanError));
+
+        assert_eq!(
+            game.takeback(),
+            Some(Move::new(Square::G1, Square::F3, None))
+        );
+        assert_eq!(
+            game.takeback(),
+            Some(Move::new(Square::E7, Square::E5, None))
+        );
+
+        let e4 = Move::new(Square::E2, Square::E4, None);
+        assert_eq!(game.moves(), &[e4]);
+        assert_eq!(*game.board(), Board::default().make_move(e4));
+
+        game.takeback();
+        assert_eq!(game.takeback(), None);
+        assert_eq!(game.board(), game.start());
+    }
+
+    #[test]
+    fn test_repetition_draws() {
+        let move_gen = MoveGen::new();
+        let mut game = Game::default();
+        let shuffle = |game: &mut Game| {
+            for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+                game.push_san(san, &move_gen).unwrap();
+            }
+        };
+
+        assert_eq!(game.repetitions(), 1);
+        shuffle(&mut game);
+        assert_eq!(game.repetitions(), 2);
+        assert_eq!(game.can_claim_draw(&move_gen), None);
+
+        shuffle(&mut game);
+        assert_eq!(game.repetitions(), 3);
+        assert_eq!(
+            game.can_claim_draw(&move_gen),
+            Some(DrawClaim::ThreefoldRepetition)
+        );
+        assert_eq!(game.is_draw(&move_gen), None);
+
+        shuffle(&mut game);
+        shuffle(&mut game);
+        assert_eq!(game.repetitions(), 5);
+        assert_eq!(game.can_claim_draw(&move_gen), None);
+        assert_eq!(
+            game.is_draw(&move_gen),
+            Some(DrawReason::FivefoldRepetition)
+        );
+
+        // The en passant square left by e4 can't be used, so the position
+        // after e4 Nf6 Nf3 Ng8 Ng1 repeats the one after e4
+        let mut game = Game::default();
+        for san in ["e4", "Nf6", "Nf3", "Ng8", "Ng1"] {
+            game.push_san(san, &move_gen).unwrap();
+        }
+        assert_eq!(game.repetitions(), 2);
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut game = Game::default();
+        game.set_tag("White", "Morphy");
+        game.set_tag("Black", "Duke Karl");
+        game.set_tag("White", "Paul Morphy");
+
+        assert_eq!(game.tag("White"), Some("Paul Morphy"));
+        assert_eq!(game.tag("Event"), None);
+        assert_eq!(game.tags().len(), 2);
+    }
+}
@@ -1,27 +1,95 @@
+pub mod bench;
 pub mod board;
+pub mod game;
 pub mod magic;
 pub mod perft;
+pub mod pgn;
+pub mod score;
 pub mod r#static;
 
-use board::{bitboard::Bitboard, color::Color, piece::Piece, r#move::Move, square::Square, Board};
+use board::{
+    bitboard::Bitboard,
+    color::Color,
+    move_list::{MoveKind, MoveList, MoveSink},
+    pawn_attack_sources,
+    piece::Piece,
+    r#move::Move,
+    square::Square,
+    Board,
+};
 use magic::SlidingMoveGen;
-use r#static::move_masks::{BLACK_PAWN_CAPTURE_MASKS, KING_MOVE_MASKS, WHITE_PAWN_CAPTURE_MASKS};
+use r#static::move_masks::KING_MOVE_MASKS;
 
 pub struct MoveGen {
     smg: SlidingMoveGen,
 }
 
+// What a position needs to check pseudolegal moves without making them
+struct Legality {
+    king_square: Square,
+    checkers: Bitboard,
+    // Squares a non-king move must land on: anywhere outside of check, the
+    // checker and the squares in between in single check, none in double check
+    evasions: Bitboard,
+    pinned: Bitboard,
+    // Used to test the king's own moves, see `Board::without_king`
+    without_king: Board,
+}
+
+impl Legality {
+    fn is_pinned(&self, square: Square) -> bool {
+        !(self.pinned & square.bitboard()).is_empty()
+    }
+
+    // Where the non-king piece on `square` may move, which for a pinned piece
+    // is only along the ray from its king through it
+    fn target_mask(&self, square: Square) -> Bitboard {
+        if !self.is_pinned(square) {
+            return self.evasions;
+        }
+
+        let Some((dr, df)) = self.king_square.direction_to(square) else {
+            return Bitboard::EMPTY;
+        };
+
+        let mut pin_ray = Bitboard::EMPTY;
+        for square in self.king_square.ray(dr, df) {
+            pin_ray |= square.bitboard();
+        }
+
+        self.evasions & pin_ray
+    }
+}
+
 impl MoveGen {
+    // Queen first, then knight as the only other promotion that is regularly useful
+    pub const PROMOTION_ORDER: [Piece; 4] =
+        [Piece::Queen, Piece::Knight, Piece::Rook, Piece::Bishop];
+
     pub fn new() -> Self {
         Self {
             smg: SlidingMoveGen::new(),
         }
     }
 
+    pub fn smg(&self) -> &SlidingMoveGen {
+        &self.smg
+    }
+
     pub fn knight_moves(board: &Board, color: Color, square: Square, moves: &mut Vec<Move>) {
+        Self::knight_moves_to_mask(board, color, square, Bitboard::UNIVERSE, moves);
+    }
+
+    fn knight_moves_to_mask(
+        board: &Board,
+        color: Color,
+        square: Square,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
         let blocker_mask = board.color_bitboard(color);
         let mut move_mask =
-            r#static::move_masks::KNIGHT_MOVE_MASKS[square as usize] & !blocker_mask;
+            r#static::move_masks::KNIGHT_MOVE_MASKS[square as usize] & !blocker_mask & target_mask;
 
         while !move_mask.is_empty() {
             let target = Square::ALL[move_mask.pop_lsb()];
@@ -30,9 +98,21 @@ impl MoveGen {
     }
 
     pub fn bishop_moves(&self, board: &Board, color: Color, square: Square, moves: &mut Vec<Move>) {
+        self.bishop_moves_to_mask(board, color, square, Bitboard::UNIVERSE, moves);
+    }
+
+    fn bishop_moves_to_mask(
+        &self,
+        board: &Board,
+        color: Color,
+        square: Square,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
         let blockers = board.all_pieces();
         let friendly_pieces = board.color_bitboard(color);
-        let mut move_mask = self.smg.bishop_moves(square, blockers) & !friendly_pieces;
+        let mut move_mask =
+            self.smg.bishop_moves(square, blockers) & !friendly_pieces & target_mask;
 
         while !move_mask.is_empty() {
             let target = Square::ALL[move_mask.pop_lsb()];
@@ -41,9 +121,20 @@ impl MoveGen {
     }
 
     pub fn rook_moves(&self, board: &Board, color: Color, square: Square, moves: &mut Vec<Move>) {
+        self.rook_moves_to_mask(board, color, square, Bitboard::UNIVERSE, moves);
+    }
+
+    fn rook_moves_to_mask(
+        &self,
+        board: &Board,
+        color: Color,
+        square: Square,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
         let blockers = board.all_pieces();
         let friendly_pieces = board.color_bitboard(color);
-        let mut move_mask = self.smg.rook_moves(square, blockers) & !friendly_pieces;
+        let mut move_mask = self.smg.rook_moves(square, blockers) & !friendly_pieces & target_mask;
 
         while !move_mask.is_empty() {
             let target = Square::ALL[move_mask.pop_lsb()];
@@ -52,13 +143,72 @@ impl MoveGen {
     }
 
     pub fn queen_moves(&self, board: &Board, color: Color, square: Square, moves: &mut Vec<Move>) {
-        self.rook_moves(board, color, square, moves);
-        self.bishop_moves(board, color, square, moves);
+        self.queen_moves_to_mask(board, color, square, Bitboard::UNIVERSE, moves);
+    }
+
+    fn queen_moves_to_mask(
+        &self,
+        board: &Board,
+        color: Color,
+        square: Square,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
+        self.rook_moves_to_mask(board, color, square, target_mask, moves);
+        self.bishop_moves_to_mask(board, color, square, target_mask, moves);
+    }
+
+    // Looks up the attacks of the slider on `square` once, then splits them
+    // into captures and quiet moves. Does nothing for non-sliding pieces.
+    pub fn slider_moves_by_kind(
+        &self,
+        board: &Board,
+        piece: Piece,
+        color: Color,
+        square: Square,
+        moves: &mut MoveList,
+    ) {
+        let blockers = board.all_pieces();
+        let attacks = match piece {
+            Piece::Bishop => self.smg.bishop_moves(square, blockers),
+            Piece::Rook => self.smg.rook_moves(square, blockers),
+            Piece::Queen => {
+                self.smg.bishop_moves(square, blockers) | self.smg.rook_moves(square, blockers)
+            }
+            _ => return,
+        };
+
+        let mut captures = attacks & board.color_bitboard(color.inverse());
+        while !captures.is_empty() {
+            let target = Square::ALL[captures.pop_lsb()];
+            moves.push(Move::new(square, target, None), MoveKind::Capture);
+        }
+
+        let mut quiets = attacks & !blockers;
+        while !quiets.is_empty() {
+            let target = Square::ALL[quiets.pop_lsb()];
+            moves.push(Move::new(square, target, None), MoveKind::Quiet);
+        }
     }
 
     pub fn king_moves(board: &Board, color: Color, square: Square, moves: &mut Vec<Move>) {
+        Self::king_moves_to_mask(board, color, square, Bitboard::UNIVERSE, moves);
+    }
+
+    fn king_moves_to_mask(
+        board: &Board,
+        color: Color,
+        square: Square,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
         let friendly_pieces = board.color_bitboard(color);
-        let mut move_mask = KING_MOVE_MASKS[square as usize] & !friendly_pieces;
+        let mut move_mask = KING_MOVE_MASKS[square as usize] & !friendly_pieces & target_mask;
+
+        // Kings can never stand next to each other
+        if let Some(enemy_king) = board.king_square(color.inverse()) {
+            move_mask &= !KING_MOVE_MASKS[enemy_king as usize];
+        }
 
         while !move_mask.is_empty() {
             let target = Square::ALL[move_mask.pop_lsb()];
@@ -66,7 +216,51 @@ impl MoveGen {
         }
     }
 
-    fn moves_with_possible_promotions(source: Square, target: Square, moves: &mut Vec<Move>) {
+    // Only checks the castling rights and that the squares between king and rook
+    // are empty. Castling out of or through check is left to the legality test.
+    fn castling_moves_to_mask(
+        board: &Board,
+        color: Color,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
+        let (home, kingside, queenside) = match color {
+            Color::White => (
+                Square::E1,
+                (Square::G1, Square::H1, Bitboard(0x60)),
+                (Square::C1, Square::A1, Bitboard(0x0e)),
+            ),
+            Color::Black => (
+                Square::E8,
+                (Square::G8, Square::H8, Bitboard(0x6000000000000000)),
+                (Square::C8, Square::A8, Bitboard(0x0e00000000000000)),
+            ),
+        };
+
+        if (board.bitboard(Piece::King, color) & home.bitboard()).is_empty() {
+            return;
+        }
+
+        let all_pieces = board.all_pieces();
+        let rooks = board.bitboard(Piece::Rook, color);
+        let sides = [
+            (board.flags.kingside(color), kingside),
+            (board.flags.queenside(color), queenside),
+        ];
+
+        // The rook is checked too, in case the rights came from a hand-built position
+        for (has_right, (target, rook, between)) in sides {
+            if has_right
+                && !(rooks & rook.bitboard()).is_empty()
+                && (between & all_pieces).is_empty()
+                && !(target.bitboard() & target_mask).is_empty()
+            {
+                moves.push(Move::new(home, target, None));
+            }
+        }
+    }
+
+    fn moves_with_possible_promotions(source: Square, target: Square, moves: &mut impl MoveSink) {
         // Rank 2 to 7
         if (8..56).contains(&(target as usize)) {
             moves.push(Move::new(source, target, None));
@@ -74,40 +268,50 @@ impl MoveGen {
         // Rank 1 and 8
         else {
             // Promotions
-            moves.push(Move::new(source, target, Some(Piece::Knight)));
-            moves.push(Move::new(source, target, Some(Piece::Bishop)));
-            moves.push(Move::new(source, target, Some(Piece::Rook)));
-            moves.push(Move::new(source, target, Some(Piece::Queen)));
+            for piece in Self::PROMOTION_ORDER {
+                moves.push(Move::new(source, target, Some(piece)));
+            }
         }
     }
 
     pub fn pawn_moves(board: &Board, color: Color, moves: &mut Vec<Move>) {
-        let all_pieces = board.all_pieces();
         let pawns = board.bitboard(Piece::Pawn, color);
+        Self::pawn_moves_to_mask(board, color, pawns, Bitboard::UNIVERSE, moves);
+    }
+
+    // Only moves the pawns in `pawns`, so pinned pawns can be given their own mask
+    fn pawn_moves_to_mask(
+        board: &Board,
+        color: Color,
+        pawns: Bitboard,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
+        let all_pieces = board.all_pieces();
         let start_rank = match color {
             Color::White => Bitboard(0x000000000000ff00), // Rank 2
             Color::Black => Bitboard(0x00ff000000000000), // Rank 7
         };
         let unmoved_pawns = pawns & start_rank;
 
-        let mut single_move_targets = match color {
-            Color::White => (pawns << 8_u8) & !all_pieces,
-            Color::Black => (pawns >> 8_u8) & !all_pieces,
+        let forward = match color {
+            Color::White => Bitboard::north,
+            Color::Black => Bitboard::south,
         };
-
-        let mut double_move_targets = match color {
-            Color::White => (((unmoved_pawns << 8_u8) & !all_pieces) << 8_u8) & !all_pieces,
-            Color::Black => (((unmoved_pawns >> 8_u8) & !all_pieces) >> 8_u8) & !all_pieces,
+        let backward = match color {
+            Color::White => Bitboard::south,
+            Color::Black => Bitboard::north,
         };
 
+        let single_moves = forward(pawns) & !all_pieces;
+        let double_moves = forward(single_moves & forward(unmoved_pawns)) & !all_pieces;
+
         // * Single moves
 
+        let mut single_move_targets = single_moves & target_mask;
         while !single_move_targets.is_empty() {
-            let target_i = single_move_targets.pop_lsb();
-            let source_i = (target_i as i8 - (8 * color.direction())) as usize;
-
-            let target = Square::ALL[target_i];
-            let source = Square::ALL[source_i];
+            let target = Square::ALL[single_move_targets.pop_lsb()];
+            let source = Square::ALL[backward(target.bitboard()).trailing_zeros() as usize];
 
             // Pawns may need to promote
             Self::moves_with_possible_promotions(source, target, moves);
@@ -115,12 +319,11 @@ impl MoveGen {
 
         // * Double moves
 
+        let mut double_move_targets = double_moves & target_mask;
         while !double_move_targets.is_empty() {
-            let target_i = double_move_targets.pop_lsb();
-            let source_i = (target_i as i8 - (16 * color.direction())) as usize;
-
-            let target = Square::ALL[target_i];
-            let source = Square::ALL[source_i];
+            let target = Square::ALL[double_move_targets.pop_lsb()];
+            let source =
+                Square::ALL[backward(backward(target.bitboard())).trailing_zeros() as usize];
 
             // Double moves never lead to promotion
             moves.push(Move::new(source, target, None));
@@ -128,29 +331,43 @@ impl MoveGen {
     }
 
     // ? This may not be the fastest solution, benchmark others and compare against this
-    fn pawn_captures(board: &Board, color: Color, moves: &mut Vec<Move>) {
-        let enemy_pieces = board.color_bitboard(color.inverse());
-        let capture_masks = match color {
-            Color::White => &WHITE_PAWN_CAPTURE_MASKS,
-            Color::Black => &BLACK_PAWN_CAPTURE_MASKS,
-        };
+    pub fn pawn_captures(board: &Board, color: Color, moves: &mut Vec<Move>) {
+        let pawns = board.bitboard(Piece::Pawn, color);
+        Self::pawn_captures_to_mask(board, color, pawns, Bitboard::UNIVERSE, moves);
+    }
 
+    fn pawn_captures_to_mask(
+        board: &Board,
+        color: Color,
+        pawns: Bitboard,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
         let en_passant = match board.en_passant_square() {
             Some(square) => square.bitboard(),
             None => Bitboard::EMPTY,
         };
+        let targets = (board.color_bitboard(color.inverse()) | en_passant) & target_mask;
+
+        // Each capture direction, paired with the step back to the capturing pawn
+        type Shift = fn(Bitboard) -> Bitboard;
+        let directions: [(Shift, Shift); 2] = match color {
+            Color::White => [
+                (Bitboard::north_west, Bitboard::south_east),
+                (Bitboard::north_east, Bitboard::south_west),
+            ],
+            Color::Black => [
+                (Bitboard::south_west, Bitboard::north_east),
+                (Bitboard::south_east, Bitboard::north_west),
+            ],
+        };
 
-        let mut pawns = board.bitboard(Piece::Pawn, color);
-        while !pawns.is_empty() {
-            let source_i = pawns.pop_lsb();
-            let source = Square::ALL[source_i];
-
-            let capture_mask = capture_masks[source_i];
-            let mut targets = (capture_mask & enemy_pieces) | (capture_mask & en_passant);
+        for (capture, back) in directions {
+            let mut captures = capture(pawns) & targets;
 
-            while !targets.is_empty() {
-                let target_i = targets.pop_lsb();
-                let target = Square::ALL[target_i];
+            while !captures.is_empty() {
+                let target = Square::ALL[captures.pop_lsb()];
+                let source = Square::ALL[back(target.bitboard()).trailing_zeros() as usize];
 
                 // Pawns may need to promote
                 Self::moves_with_possible_promotions(source, target, moves);
@@ -158,7 +375,19 @@ impl MoveGen {
         }
     }
 
+    // The order of generated moves is stable and may be relied on: knights,
+    // bishops, rooks, queens, king, castling, single pawn pushes, double pawn pushes, then
+    // pawn captures. Pieces are taken from a1 to h8 and their targets likewise.
+    // Pawn moves are ordered by target square rather than by pawn, with all
+    // captures towards the a-file before those towards the h-file (b2a3, d2c3,
+    // b2c3, d2e3), and promotions in `PROMOTION_ORDER`. `legal_moves` doesn't
+    // keep this order.
     pub fn pseudolegal_moves(&self, board: &Board, moves: &mut Vec<Move>) {
+        self.moves_to_mask(board, Bitboard::UNIVERSE, moves);
+    }
+
+    // Generates the pseudolegal moves whose target square is in `target_mask`
+    pub fn moves_to_mask(&self, board: &Board, target_mask: Bitboard, moves: &mut impl MoveSink) {
         let friendly_color = board.active_color;
 
         // Knight moves
@@ -168,7 +397,7 @@ impl MoveGen {
             let i = knight_bitboard.pop_lsb();
             let from_square = Square::ALL[i];
 
-            Self::knight_moves(board, friendly_color, from_square, moves);
+            Self::knight_moves_to_mask(board, friendly_color, from_square, target_mask, moves);
         }
 
         // Bishop moves
@@ -178,7 +407,7 @@ impl MoveGen {
             let i = bishop_bitboard.pop_lsb();
             let from_square = Square::ALL[i];
 
-            self.bishop_moves(board, friendly_color, from_square, moves);
+            self.bishop_moves_to_mask(board, friendly_color, from_square, target_mask, moves);
         }
 
         // Rook moves
@@ -188,7 +417,7 @@ impl MoveGen {
             let i = rook_bitboard.pop_lsb();
             let from_square = Square::ALL[i];
 
-            self.rook_moves(board, friendly_color, from_square, moves);
+            self.rook_moves_to_mask(board, friendly_color, from_square, target_mask, moves);
         }
 
         // Queen moves
@@ -198,20 +427,411 @@ impl MoveGen {
             let i = queen_bitboard.pop_lsb();
             let from_square = Square::ALL[i];
 
-            self.queen_moves(board, friendly_color, from_square, moves);
+            self.queen_moves_to_mask(board, friendly_color, from_square, target_mask, moves);
         }
 
         // King moves
-        let king_i = board.bitboard(Piece::King, friendly_color).trailing_zeros() as usize;
-        let king_square = Square::ALL[king_i];
+        if let Some(king_square) = board.king_square(friendly_color) {
+            Self::king_moves_to_mask(board, friendly_color, king_square, target_mask, moves);
+        }
+        Self::castling_moves_to_mask(board, friendly_color, target_mask, moves);
 
-        Self::king_moves(board, friendly_color, king_square, moves);
+        let pawns = board.bitboard(Piece::Pawn, friendly_color);
 
         // Pawn moves
-        Self::pawn_moves(board, friendly_color, moves);
+        Self::pawn_moves_to_mask(board, friendly_color, pawns, target_mask, moves);
 
         // Pawn captures
-        Self::pawn_captures(board, friendly_color, moves);
+        Self::pawn_captures_to_mask(board, friendly_color, pawns, target_mask, moves);
+    }
+
+    // Pseudolegal captures, most valuable victim first and, between captures of
+    // equal victims, least valuable attacker first (MVV-LVA). Each victim gets
+    // its own pass, so the moves come out in order without sorting.
+    pub fn capture_moves_ordered(&self, board: &Board, moves: &mut Vec<Move>) {
+        let color = board.active_color;
+        let en_passant = board
+            .en_passant_square()
+            .map_or(Bitboard::EMPTY, |square| square.bitboard());
+
+        for victim in [
+            Piece::Queen,
+            Piece::Rook,
+            Piece::Bishop,
+            Piece::Knight,
+            Piece::Pawn,
+        ] {
+            let victims = board.bitboard(victim, color.inverse());
+
+            // Only pawns can capture onto the en passant square
+            let pawn_victims = match victim {
+                Piece::Pawn => victims | en_passant,
+                _ => victims,
+            };
+            let pawns = board.bitboard(Piece::Pawn, color);
+            Self::pawn_captures_to_mask(board, color, pawns, pawn_victims, moves);
+
+            for attacker in [
+                Piece::Knight,
+                Piece::Bishop,
+                Piece::Rook,
+                Piece::Queen,
+                Piece::King,
+            ] {
+                let mut attackers = board.bitboard(attacker, color);
+
+                while !attackers.is_empty() {
+                    let square = Square::ALL[attackers.pop_lsb()];
+                    self.piece_moves_to_mask(board, attacker, color, square, victims, moves);
+                }
+            }
+        }
+    }
+
+    // Moves of the non-pawn `piece` on `square`, without castling
+    fn piece_moves_to_mask(
+        &self,
+        board: &Board,
+        piece: Piece,
+        color: Color,
+        square: Square,
+        target_mask: Bitboard,
+        moves: &mut impl MoveSink,
+    ) {
+        match piece {
+            Piece::Knight => Self::knight_moves_to_mask(board, color, square, target_mask, moves),
+            Piece::Bishop => self.bishop_moves_to_mask(board, color, square, target_mask, moves),
+            Piece::Rook => self.rook_moves_to_mask(board, color, square, target_mask, moves),
+            Piece::Queen => self.queen_moves_to_mask(board, color, square, target_mask, moves),
+            Piece::King => Self::king_moves_to_mask(board, color, square, target_mask, moves),
+            Piece::Pawn => {}
+        }
+    }
+
+    // Pinned pieces only generate along their pin ray and, in check, only
+    // evasions are generated, so just king moves and en passant need testing
+    pub fn legal_moves(&self, board: &Board, moves: &mut Vec<Move>) {
+        self.legal_moves_filtered(board, |_| true, moves);
+    }
+
+    // Legal moves matching `pred`. Checks and pins are still found up front,
+    // but `pred` runs before any move is tested by itself for legality.
+    pub fn legal_moves_filtered(
+        &self,
+        board: &Board,
+        pred: impl Fn(Move) -> bool,
+        moves: &mut Vec<Move>,
+    ) {
+        self.legal_moves_into(board, pred, moves);
+    }
+
+    // For hot loops that want an owned list. Moves are generated straight into
+    // it, so it only spills to the heap for positions with over 64 legal moves.
+    #[cfg(feature = "smallvec")]
+    pub fn legal_moves_owned(&self, board: &Board) -> smallvec::SmallVec<[Move; 64]> {
+        let mut moves = smallvec::SmallVec::new();
+        self.legal_moves_into(board, |_| true, &mut moves);
+        moves
+    }
+
+    fn legal_moves_into(
+        &self,
+        board: &Board,
+        pred: impl Fn(Move) -> bool,
+        moves: &mut impl MoveSink,
+    ) {
+        // Without a king to expose, every pseudolegal move is legal
+        let Some(legality) = self.legality(board) else {
+            let mut moves = Filtered { moves, pred };
+            self.moves_to_mask(board, Bitboard::UNIVERSE, &mut moves);
+            return;
+        };
+
+        self.legal_king_moves(board, &legality, &pred, moves);
+
+        // Only the king can escape a double check
+        if legality.checkers.count() > 1 {
+            return;
+        }
+
+        let color = board.active_color;
+        let mut filtered = Filtered { moves, pred: &pred };
+        for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+            let mut pieces = board.bitboard(piece, color);
+
+            while !pieces.is_empty() {
+                let square = Square::ALL[pieces.pop_lsb()];
+                self.legal_piece_moves(board, &legality, piece, square, &mut filtered);
+            }
+        }
+
+        self.legal_pawn_moves(board, &legality, &pred, moves);
+    }
+
+    // Legal moves of the knight, bishop, rook or queen on `square`
+    fn legal_piece_moves(
+        &self,
+        board: &Board,
+        legality: &Legality,
+        piece: Piece,
+        square: Square,
+        moves: &mut impl MoveSink,
+    ) {
+        // A knight can never move along its pin ray
+        if piece == Piece::Knight && legality.is_pinned(square) {
+            return;
+        }
+
+        let color = board.active_color;
+        let target_mask = legality.target_mask(square);
+        self.piece_moves_to_mask(board, piece, color, square, target_mask, moves);
+    }
+
+    // The king's targets can't be masked in advance, so its moves and castling
+    // are each checked against the board without the king once `pred` passes
+    fn legal_king_moves(
+        &self,
+        board: &Board,
+        legality: &Legality,
+        pred: &impl Fn(Move) -> bool,
+        moves: &mut impl MoveSink,
+    ) {
+        let color = board.active_color;
+        let mut moves = Filtered {
+            moves,
+            pred: |mv| pred(mv) && self.is_legal_given(board, Some(legality), mv),
+        };
+
+        Self::king_moves_to_mask(
+            board,
+            color,
+            legality.king_square,
+            Bitboard::UNIVERSE,
+            &mut moves,
+        );
+        Self::castling_moves_to_mask(board, color, Bitboard::UNIVERSE, &mut moves);
+    }
+
+    fn legal_pawn_moves(
+        &self,
+        board: &Board,
+        legality: &Legality,
+        pred: &impl Fn(Move) -> bool,
+        moves: &mut impl MoveSink,
+    ) {
+        let color = board.active_color;
+        let mut filtered = Filtered { moves, pred };
+        let pawns = board.bitboard(Piece::Pawn, color);
+        let en_passant = board.en_passant_square();
+        let not_en_passant = !en_passant.map_or(Bitboard::EMPTY, |square| square.bitboard());
+
+        let free = pawns & !legality.pinned;
+        Self::pawn_moves_to_mask(board, color, free, legality.evasions, &mut filtered);
+        Self::pawn_captures_to_mask(
+            board,
+            color,
+            free,
+            legality.evasions & not_en_passant,
+            &mut filtered,
+        );
+
+        let mut pinned = pawns & legality.pinned;
+        while !pinned.is_empty() {
+            let square = Square::ALL[pinned.pop_lsb()];
+            let target_mask = legality.target_mask(square);
+
+            Self::pawn_moves_to_mask(board, color, square.bitboard(), target_mask, &mut filtered);
+            Self::pawn_captures_to_mask(
+                board,
+                color,
+                square.bitboard(),
+                target_mask & not_en_passant,
+                &mut filtered,
+            );
+        }
+
+        // En passant removes two pawns from the same rank, which may expose the
+        // king along it, so it is tested by making the move
+        if let Some(square) = en_passant {
+            let mut sources = pawn_attack_sources(square, color) & pawns;
+
+            while !sources.is_empty() {
+                let mv = Move::new(Square::ALL[sources.pop_lsb()], square, None);
+
+                if pred(mv) && self.is_legal_by_make_move(board, mv) {
+                    moves.push(mv);
+                }
+            }
+        }
+    }
+
+    // Pseudolegal `mv` is legal if it doesn't leave the mover's king in check
+    pub fn is_legal(&self, board: &Board, mv: Move) -> bool {
+        self.is_legal_given(board, self.legality(board).as_ref(), mv)
+    }
+
+    // Slower than `is_legal`, but doesn't depend on pin or check detection
+    pub fn is_legal_by_make_move(&self, board: &Board, mv: Move) -> bool {
+        let color = board.active_color;
+
+        if let Some((_, passed)) = board.castling_rook_move(mv) {
+            if board.is_in_check(color, &self.smg)
+                || board.is_attacked(passed, color.inverse(), &self.smg)
+            {
+                return false;
+            }
+        }
+
+        !board.make_move(mv).is_in_check(color, &self.smg)
+    }
+
+    // `None` if the side to move has no king, in which case every move is legal
+    fn legality(&self, board: &Board) -> Option<Legality> {
+        let color = board.active_color;
+        let king_square = board.king_square(color)?;
+
+        let checkers = board.checkers(color, &self.smg);
+        let evasions = match checkers.count() {
+            0 => Bitboard::UNIVERSE,
+            1 => {
+                let checker = Square::ALL[checkers.trailing_zeros() as usize];
+                Bitboard::between(king_square, checker) | checkers
+            }
+            _ => Bitboard::EMPTY,
+        };
+
+        Some(Legality {
+            king_square,
+            checkers,
+            evasions,
+            pinned: board.pinned_pieces(color, &self.smg),
+            without_king: board.without_king(color),
+        })
+    }
+
+    fn is_legal_given(&self, board: &Board, legality: Option<&Legality>, mv: Move) -> bool {
+        let Some(legality) = legality else {
+            return self.is_legal_by_make_move(board, mv);
+        };
+
+        let source = mv.source();
+        let target = mv.target();
+        let king_square = legality.king_square;
+
+        if source == king_square {
+            let enemy = board.active_color.inverse();
+
+            // The king can't castle out of check, or through the rook's target square
+            if let Some((_, passed)) = board.castling_rook_move(mv) {
+                if !legality.checkers.is_empty() || board.is_attacked(passed, enemy, &self.smg) {
+                    return false;
+                }
+            }
+
+            return !legality.without_king.is_attacked(target, enemy, &self.smg);
+        }
+
+        if board.is_en_passant(mv) {
+            return self.is_legal_by_make_move(board, mv);
+        }
+
+        !(legality.target_mask(source) & target.bitboard()).is_empty()
+    }
+
+    // Stops at the first piece found with a legal move, trying the king first
+    // as it is the only piece that can escape a double check
+    pub fn has_any_legal_move(&self, board: &Board) -> bool {
+        let mut found = AnyMove(false);
+
+        let Some(legality) = self.legality(board) else {
+            self.moves_to_mask(board, Bitboard::UNIVERSE, &mut found);
+            return found.0;
+        };
+
+        self.legal_king_moves(board, &legality, &|_| true, &mut found);
+        if found.0 || legality.checkers.count() > 1 {
+            return found.0;
+        }
+
+        let color = board.active_color;
+        for piece in [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+            let mut pieces = board.bitboard(piece, color);
+
+            while !pieces.is_empty() {
+                let square = Square::ALL[pieces.pop_lsb()];
+                self.legal_piece_moves(board, &legality, piece, square, &mut found);
+
+                if found.0 {
+                    return true;
+                }
+            }
+        }
+
+        self.legal_pawn_moves(board, &legality, &|_| true, &mut found);
+        found.0
+    }
+
+    // Only generates evasions: king moves, then captures of or blocks
+    // against a single checker
+    pub fn is_checkmate(&self, board: &Board) -> bool {
+        let Some(legality) = self.legality(board) else {
+            return false;
+        };
+        if legality.checkers.is_empty() {
+            return false;
+        }
+
+        let color = board.active_color;
+        let mut moves = Vec::new();
+        Self::king_moves(board, color, legality.king_square, &mut moves);
+
+        if moves
+            .iter()
+            .any(|mv| self.is_legal_given(board, Some(&legality), *mv))
+        {
+            return false;
+        }
+
+        if legality.checkers.count() > 1 {
+            return true;
+        }
+
+        let checker = Square::ALL[legality.checkers.trailing_zeros() as usize];
+        let mut evasions = Bitboard::between(legality.king_square, checker) | legality.checkers;
+
+        // A pawn checker that just double pushed may be taken en passant
+        if let Some(square) = board.en_passant_square() {
+            evasions |= square.bitboard();
+        }
+
+        moves.clear();
+        self.moves_to_mask(board, evasions, &mut moves);
+        !moves.iter().any(|mv| {
+            mv.source() != legality.king_square && self.is_legal_given(board, Some(&legality), *mv)
+        })
+    }
+}
+
+// Passes on only the moves matching `pred`
+struct Filtered<'a, S, F> {
+    moves: &'a mut S,
+    pred: F,
+}
+
+impl<S: MoveSink, F: Fn(Move) -> bool> MoveSink for Filtered<'_, S, F> {
+    fn push(&mut self, mv: Move) {
+        if (self.pred)(mv) {
+            self.moves.push(mv);
+        }
+    }
+}
+
+// Only records whether any move was pushed
+struct AnyMove(bool);
+
+impl MoveSink for AnyMove {
+    fn push(&mut self, _: Move) {
+        self.0 = true;
     }
 }
 
@@ -223,7 +843,7 @@ impl Default for MoveGen {
 
 #[cfg(test)]
 mod tests {
-    use board::{bitboard::Bitboard, r#move};
+    use board::bitboard::Bitboard;
 
     use super::*;
 
@@ -337,7 +957,7 @@ mod tests {
             mask |= mv.target().bitboard();
         }
 
-        assert_eq!(mask, Bitboard(0x804000));
+        assert_eq!(mask, Bitboard(0x805000));
     }
 
     #[test]
@@ -392,6 +1012,553 @@ mod tests {
         assert_eq!(mask, Bitboard(0x3828300000));
     }
 
+    #[test]
+    fn test_pseudolegal_move_order() {
+        let move_gen = MoveGen::new();
+        let mut moves = Vec::new();
+        move_gen.pseudolegal_moves(&Board::default(), &mut moves);
+
+        let uci = moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            uci,
+            [
+                "b1a3", "b1c3", "g1f3", "g1h3", "a2a3", "b2b3", "c2c3", "d2d3", "e2e3", "f2f3",
+                "g2g3", "h2h3", "a2a4", "b2b4", "c2c4", "d2d4", "e2e4", "f2f4", "g2g4", "h2h4",
+            ]
+        );
+
+        let board = Board::from_fen("k6r/6P1/8/8/8/n1p1p3/1P1P4/7K w - - 0 1").unwrap();
+        moves.clear();
+        move_gen.pseudolegal_moves(&board, &mut moves);
+
+        let uci = moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            uci,
+            [
+                "h1g1", "h1g2", "h1h2", "b2b3", "d2d3", "g7g8q", "g7g8n", "g7g8r", "g7g8b", "b2b4",
+                "d2d4", "b2a3", "d2c3", "b2c3", "d2e3", "g7h8q", "g7h8n", "g7h8r", "g7h8b",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_moves_ordered() {
+        let move_gen = MoveGen::new();
+        let board = Board::from_fen("4k3/8/p1q5/1P3r2/3N4/8/8/4K2Q w - - 0 1").unwrap();
+        let mut moves = Vec::new();
+        move_gen.capture_moves_ordered(&board, &mut moves);
+
+        assert_eq!(
+            moves,
+            [
+                Move::new(Square::B5, Square::C6, None),
+                Move::new(Square::D4, Square::C6, None),
+                Move::new(Square::H1, Square::C6, None),
+                Move::new(Square::D4, Square::F5, None),
+                Move::new(Square::B5, Square::A6, None),
+            ]
+        );
+
+        // The knight can reach the en passant square, but only the pawn captures there
+        let board = Board::from_fen("4k3/8/8/8/3pP1n1/8/8/4K3 b - e3 0 1").unwrap();
+        moves.clear();
+        move_gen.capture_moves_ordered(&board, &mut moves);
+
+        assert_eq!(moves, [Move::new(Square::D4, Square::E3, None)]);
+    }
+
+    #[test]
+    fn test_moves_to_mask() {
+        let board = Board::default();
+        let move_gen = MoveGen::new();
+
+        let mut moves = Vec::new();
+        move_gen.moves_to_mask(&board, Square::F3.bitboard(), &mut moves);
+
+        assert_eq!(moves.len(), 2);
+        for mv in &moves {
+            assert_eq!(mv.target(), Square::F3);
+        }
+
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        moves.clear();
+        move_gen.moves_to_mask(&board, Square::D5.bitboard(), &mut moves);
+
+        assert_eq!(moves, vec![Move::new(Square::E4, Square::D5, None)]);
+
+        moves.clear();
+        move_gen.moves_to_mask(&board, Bitboard::EMPTY, &mut moves);
+
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    fn test_legal_moves_pins() {
+        let move_gen = MoveGen::new();
+
+        // Rook pinned on the e-file
+        let board = Board::from_fen("4r2k/8/8/8/4R3/8/8/4K3 w - - 0 1").unwrap();
+        let mut moves = Vec::new();
+        move_gen.legal_moves(&board, &mut moves);
+
+        let mut rook_targets = moves
+            .iter()
+            .filter(|mv| mv.source() == Square::E4)
+            .map(|mv| mv.target())
+            .collect::<Vec<Square>>();
+        rook_targets.sort();
+
+        assert_eq!(
+            rook_targets,
+            vec![
+                Square::E2,
+                Square::E3,
+                Square::E5,
+                Square::E6,
+                Square::E7,
+                Square::E8
+            ]
+        );
+
+        // Knight pinned on the first rank, bishop pinned diagonally
+        let board = Board::from_fen("7k/8/5b2/8/8/2B5/8/K1N4q w - - 0 1").unwrap();
+        moves.clear();
+        move_gen.legal_moves(&board, &mut moves);
+
+        assert!(moves.iter().all(|mv| mv.source() != Square::C1));
+
+        let mut bishop_targets = moves
+            .iter()
+            .filter(|mv| mv.source() == Square::C3)
+            .map(|mv| mv.target())
+            .collect::<Vec<Square>>();
+        bishop_targets.sort();
+
+        assert_eq!(
+            bishop_targets,
+            vec![Square::B2, Square::D4, Square::E5, Square::F6]
+        );
+
+        // Pawns pinned on a file can only push, and diagonally can only take the pinner
+        let pawn_targets = |fen: &str| {
+            let board = Board::from_fen(fen).unwrap();
+            let mut moves = Vec::new();
+            move_gen.legal_moves(&board, &mut moves);
+
+            let mut targets = moves
+                .iter()
+                .filter(|mv| mv.source() == Square::E2 || mv.source() == Square::D2)
+                .map(|mv| mv.target())
+                .collect::<Vec<Square>>();
+            targets.sort();
+            targets
+        };
+
+        assert_eq!(
+            pawn_targets("4r2k/8/8/8/8/5b2/4P3/4K3 w - - 0 1"),
+            vec![Square::E3, Square::E4]
+        );
+        assert_eq!(
+            pawn_targets("4k3/8/8/8/8/2b5/3P4/4K3 w - - 0 1"),
+            vec![Square::C3]
+        );
+    }
+
+    #[test]
+    fn test_legal_moves_filtered() {
+        let move_gen = MoveGen::new();
+        let is_knight_move = |board: &Board| {
+            let board = *board;
+            move |mv: Move| board.piece_at(mv.source()) == Some(Piece::Knight)
+        };
+
+        let board = Board::default();
+        let mut moves = Vec::new();
+        move_gen.legal_moves_filtered(&board, is_knight_move(&board), &mut moves);
+        moves.sort();
+        assert_eq!(
+            moves,
+            [
+                Move::new(Square::B1, Square::A3, None),
+                Move::new(Square::B1, Square::C3, None),
+                Move::new(Square::G1, Square::F3, None),
+                Move::new(Square::G1, Square::H3, None),
+            ]
+        );
+
+        // The knight on d2 is pinned, leaving only the one on g1
+        let board = Board::from_fen("4k3/8/8/8/1b6/8/3N4/4K1N1 w - - 0 1").unwrap();
+        moves.clear();
+        move_gen.legal_moves_filtered(&board, is_knight_move(&board), &mut moves);
+        assert_eq!(moves.len(), 3);
+        assert!(moves.iter().all(|mv| mv.source() == Square::G1));
+    }
+
+    #[test]
+    fn test_legal_moves_filtered_checks_pred_first() {
+        let move_gen = MoveGen::new();
+
+        // Kb4, Kb5, Kb6 walk into the rook on b8 and exd6 exposes the king
+        // to the rook on h5, so `pred` only sees them if it runs first
+        let board = Board::from_fen("1r6/8/8/K2pP2r/8/8/8/4k3 w - d6 0 1").unwrap();
+        let seen = std::cell::RefCell::new(Vec::new());
+        let mut moves = Vec::new();
+        move_gen.legal_moves_filtered(
+            &board,
+            |mv| {
+                seen.borrow_mut().push(mv);
+                false
+            },
+            &mut moves,
+        );
+
+        assert!(moves.is_empty());
+        let seen = seen.into_inner();
+        for mv in [
+            Move::new(Square::A5, Square::B4, None),
+            Move::new(Square::A5, Square::B5, None),
+            Move::new(Square::A5, Square::B6, None),
+            Move::new(Square::E5, Square::D6, None),
+        ] {
+            assert!(!move_gen.is_legal(&board, mv));
+            assert_eq!(seen.iter().filter(|&&seen| seen == mv).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_legal_moves_king_captures() {
+        let move_gen = MoveGen::new();
+
+        // The checking rook on e2 is defended by the pawn on d3
+        let board = Board::from_fen("4k3/8/8/8/8/3p4/4r3/4K3 w - - 0 1").unwrap();
+        let mut moves = Vec::new();
+        move_gen.legal_moves(&board, &mut moves);
+
+        assert!(!moves.contains(&Move::new(Square::E1, Square::E2, None)));
+        assert!(moves.iter().all(|mv| mv.source() == Square::E1));
+
+        // Undefended, it can be taken
+        let board = Board::from_fen("4k3/8/8/8/8/8/4r3/4K3 w - - 0 1").unwrap();
+        moves.clear();
+        move_gen.legal_moves(&board, &mut moves);
+
+        assert!(moves.contains(&Move::new(Square::E1, Square::E2, None)));
+
+        // Stepping back along the checking ray to e1 is still in check
+        let board = Board::from_fen("4k3/8/8/4r3/8/8/4K3/8 w - - 0 1").unwrap();
+        moves.clear();
+        move_gen.legal_moves(&board, &mut moves);
+
+        moves.sort();
+        assert_eq!(
+            moves,
+            [
+                Square::D1,
+                Square::F1,
+                Square::D2,
+                Square::F2,
+                Square::D3,
+                Square::F3
+            ]
+            .map(|target| Move::new(Square::E2, target, None))
+            .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_legal_moves_en_passant() {
+        let move_gen = MoveGen::new();
+        let en_passant = Move::new(Square::E5, Square::D6, None);
+        let mut moves = Vec::new();
+
+        // Both pawns leave the rank, exposing the king to the rook
+        let board = Board::from_fen("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1").unwrap();
+        move_gen.legal_moves(&board, &mut moves);
+        assert!(!moves.contains(&en_passant));
+        assert!(moves.contains(&Move::new(Square::E5, Square::E6, None)));
+
+        // Taking the checking pawn, although it isn't on the target square
+        let board = Board::from_fen("8/8/8/3pP3/4K3/8/8/7k w - d6 0 1").unwrap();
+        moves.clear();
+        move_gen.legal_moves(&board, &mut moves);
+        assert!(moves.contains(&en_passant));
+        assert!(!moves.contains(&Move::new(Square::E5, Square::E6, None)));
+    }
+
+    #[test]
+    fn test_legal_moves_match_make_and_test() {
+        let move_gen = MoveGen::new();
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ];
+
+        for fen in fens {
+            let board = Board::from_fen(fen).unwrap();
+
+            let mut expected = Vec::new();
+            move_gen.pseudolegal_moves(&board, &mut expected);
+            expected.retain(|mv| move_gen.is_legal_by_make_move(&board, *mv));
+            expected.sort();
+
+            let mut moves = Vec::new();
+            move_gen.legal_moves(&board, &mut moves);
+            moves.sort();
+
+            assert_eq!(moves, expected, "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_slider_moves_by_kind() {
+        let move_gen = MoveGen::new();
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let color = board.active_color;
+
+        for piece in [Piece::Bishop, Piece::Rook, Piece::Queen] {
+            let mut sliders = board.bitboard(piece, color);
+            while !sliders.is_empty() {
+                let square = Square::ALL[sliders.pop_lsb()];
+
+                let mut expected = Vec::new();
+                match piece {
+                    Piece::Bishop => move_gen.bishop_moves(&board, color, square, &mut expected),
+                    Piece::Rook => move_gen.rook_moves(&board, color, square, &mut expected),
+                    _ => move_gen.queen_moves(&board, color, square, &mut expected),
+                }
+                expected.sort();
+
+                let mut list = MoveList::new();
+                move_gen.slider_moves_by_kind(&board, piece, color, square, &mut list);
+
+                let mut moves = list.moves().collect::<Vec<Move>>();
+                moves.sort();
+                assert_eq!(moves, expected);
+
+                for (mv, kind) in list.iter() {
+                    let is_capture = board.color_at(mv.target()) == Some(color.inverse());
+                    assert_eq!(kind == MoveKind::Capture, is_capture);
+                }
+            }
+        }
+
+        // Qf3 takes on f6 and h3
+        let mut list = MoveList::new();
+        move_gen.slider_moves_by_kind(&board, Piece::Queen, color, Square::F3, &mut list);
+        let mut captures = list.captures().collect::<Vec<Move>>();
+        captures.sort();
+        assert_eq!(
+            captures,
+            vec![
+                Move::new(Square::F3, Square::H3, None),
+                Move::new(Square::F3, Square::F6, None),
+            ]
+        );
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_legal_moves_owned() {
+        let move_gen = MoveGen::new();
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+
+        let mut moves = Vec::new();
+        move_gen.legal_moves(&board, &mut moves);
+
+        let owned = move_gen.legal_moves_owned(&board);
+        assert_eq!(owned.to_vec(), moves);
+
+        // Kiwipete has 48 legal moves, which fit inline
+        assert!(!owned.spilled());
+    }
+
+    #[test]
+    fn test_is_legal_pins_and_checks() {
+        let move_gen = MoveGen::new();
+
+        // A knight pinned on the e-file can't move at all
+        let board = Board::from_fen("k3r3/8/8/8/4N3/8/8/4K3 w - - 0 1").unwrap();
+        assert!(!move_gen.is_legal(&board, Move::new(Square::E4, Square::C5, None)));
+
+        // A rook pinned on the e-file may slide along it, but not leave it
+        let board = Board::from_fen("k3r3/8/8/8/4R3/8/8/4K3 w - - 0 1").unwrap();
+        assert!(move_gen.is_legal(&board, Move::new(Square::E4, Square::E8, None)));
+        assert!(move_gen.is_legal(&board, Move::new(Square::E4, Square::E2, None)));
+        assert!(!move_gen.is_legal(&board, Move::new(Square::E4, Square::D4, None)));
+
+        // In check, only blocks, captures of the checker and king moves
+        let board = Board::from_fen("k3r3/8/8/8/8/8/3R4/4K3 w - - 0 1").unwrap();
+        assert!(move_gen.is_legal(&board, Move::new(Square::D2, Square::E2, None)));
+        assert!(!move_gen.is_legal(&board, Move::new(Square::D2, Square::D8, None)));
+        assert!(move_gen.is_legal(&board, Move::new(Square::E1, Square::D1, None)));
+        assert!(!move_gen.is_legal(&board, Move::new(Square::E1, Square::E2, None)));
+
+        // Double check leaves only king moves
+        let board = Board::from_fen("k3r3/8/8/8/1b6/8/3R4/4K3 w - - 0 1").unwrap();
+        assert!(!move_gen.is_legal(&board, Move::new(Square::D2, Square::E2, None)));
+        assert!(move_gen.is_legal(&board, Move::new(Square::E1, Square::F2, None)));
+    }
+
+    #[test]
+    fn test_castling_moves() {
+        let move_gen = MoveGen::new();
+        let castles = |fen: &str| {
+            let board = Board::from_fen(fen).unwrap();
+            let mut moves = Vec::new();
+            move_gen.legal_moves(&board, &mut moves);
+
+            let mut castles = moves
+                .into_iter()
+                .filter(|mv| board.castling_rook_move(*mv).is_some())
+                .map(|mv| mv.to_string())
+                .collect::<Vec<_>>();
+            castles.sort();
+            castles
+        };
+
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+            ["e1c1", "e1g1"]
+        );
+        assert_eq!(castles("r3k2r/8/8/8/8/8/8/R3K2R b Kq - 0 1"), ["e8c8"]);
+
+        // Blocked, and the knight on b1 also blocks queenside castling
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/RN2K1NR w KQkq - 0 1"),
+            [] as [&str; 0]
+        );
+
+        // Out of check, through check, and into check
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/4r3/8/R3K2R w KQkq - 0 1"),
+            [] as [&str; 0]
+        );
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/3r1r2/8/R3K2R w KQkq - 0 1"),
+            [] as [&str; 0]
+        );
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/2r3r1/8/R3K2R w KQkq - 0 1"),
+            [] as [&str; 0]
+        );
+
+        // Rights without the rook, or with an enemy rook in the corner
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        *board.piece_bitboard_mut(Piece::Rook) ^= Square::H1.bitboard();
+        *board.color_bitboard_mut(Color::White) ^= Square::H1.bitboard();
+        let mut moves = Vec::new();
+        move_gen.legal_moves(&board, &mut moves);
+        assert!(!moves.contains(&Move::new(Square::E1, Square::G1, None)));
+        assert!(moves.contains(&Move::new(Square::E1, Square::C1, None)));
+
+        board.add_piece(Piece::Rook, Color::Black, Square::H1);
+        moves.clear();
+        move_gen.legal_moves(&board, &mut moves);
+        assert!(!moves.contains(&Move::new(Square::E1, Square::G1, None)));
+
+        // Only the squares the king crosses need to be safe
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/1r6/8/R3K2R w KQkq - 0 1"),
+            ["e1c1", "e1g1"]
+        );
+    }
+
+    #[test]
+    fn test_king_moves_avoid_enemy_king() {
+        let board = Board::from_fen("8/8/8/4k3/8/4K3/8/8 w - - 0 1").unwrap();
+
+        let mut moves = Vec::new();
+        MoveGen::king_moves(&board, Color::White, Square::E3, &mut moves);
+        moves.sort();
+
+        assert_eq!(
+            moves,
+            [Square::D2, Square::E2, Square::F2, Square::D3, Square::F3]
+                .map(|target| Move::new(Square::E3, target, None))
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_has_any_legal_move() {
+        let move_gen = MoveGen::new();
+
+        assert!(move_gen.has_any_legal_move(&Board::default()));
+
+        // Fool's mate
+        let mated =
+            Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+                .unwrap();
+        assert!(!move_gen.has_any_legal_move(&mated));
+
+        let stalemated = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert!(!move_gen.has_any_legal_move(&stalemated));
+
+        // In check, but able to capture the checker
+        let check = Board::from_fen("7k/6Q1/8/8/8/8/8/K7 b - - 0 1").unwrap();
+        assert!(move_gen.has_any_legal_move(&check));
+
+        // The king is boxed in, so only the pawn can move
+        let pawn_only = Board::from_fen("1r5k/8/8/8/8/8/P7/K7 w - - 0 1").unwrap();
+        assert!(move_gen.has_any_legal_move(&pawn_only));
+
+        let blocked = Board::from_fen("1r5k/8/8/8/8/p7/P7/K7 w - - 0 1").unwrap();
+        assert!(!move_gen.has_any_legal_move(&blocked));
+
+        // Agrees with full generation on every position two plies into kiwipete
+        let kiwipete =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let mut first = Vec::new();
+        move_gen.legal_moves(&kiwipete, &mut first);
+
+        for mv in first {
+            let board = kiwipete.make_move(mv);
+            let mut second = Vec::new();
+            move_gen.legal_moves(&board, &mut second);
+
+            for mv in second {
+                let board = board.make_move(mv);
+                let mut moves = Vec::new();
+                move_gen.legal_moves(&board, &mut moves);
+
+                assert_eq!(
+                    move_gen.has_any_legal_move(&board),
+                    !moves.is_empty(),
+                    "{}",
+                    board.fen()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_checkmate() {
+        let move_gen = MoveGen::new();
+
+        let smothered = Board::from_fen("6rk/5Npp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert!(move_gen.is_checkmate(&smothered));
+
+        let back_rank = Board::from_fen("6k1/8/8/8/8/8/5PPP/r5K1 w - - 0 1").unwrap();
+        assert!(move_gen.is_checkmate(&back_rank));
+
+        // The knight can block on b1 or f1
+        let blockable = Board::from_fen("6k1/8/8/8/8/8/3N1PPP/r5K1 w - - 0 1").unwrap();
+        assert!(!move_gen.is_checkmate(&blockable));
+
+        let stalemated = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert!(!move_gen.is_checkmate(&stalemated));
+
+        assert!(!move_gen.is_checkmate(&Board::default()));
+    }
+
     #[test]
     fn test_pawn_moves() {
         let mut board = Board::new();
@@ -462,6 +1629,26 @@ mod tests {
         assert_eq!(moves[0], Move::new(Square::B3, Square::A2, None));
     }
 
+    #[test]
+    fn test_promotion_order() {
+        let mut board = Board::new();
+        board.add_piece(Piece::Pawn, Color::White, Square::B7);
+
+        let mut moves = Vec::new();
+        MoveGen::pawn_moves(&board, Color::White, &mut moves);
+
+        let promotions = moves.iter().map(|mv| mv.promotion()).collect::<Vec<_>>();
+        assert_eq!(
+            promotions,
+            vec![
+                Some(Piece::Queen),
+                Some(Piece::Knight),
+                Some(Piece::Rook),
+                Some(Piece::Bishop)
+            ]
+        );
+    }
+
     #[test]
     fn test_pawn_en_passant() {
         let mut board = Board::new();
@@ -2,7 +2,7 @@ use rand::{self, thread_rng, Rng};
 
 use crate::{
     board::{bitboard::Bitboard, square::Square},
-    r#static::generation::{bishop_move_mask, coords, in_bounds, rook_move_mask},
+    r#static::generation::{bishop_move_mask, coords, rook_move_mask},
 };
 
 #[derive(Debug)]
@@ -47,6 +47,15 @@ impl SlidingMoveGen {
         let i = square as usize;
         self.bishop_tables[i][magic_index(&self.bishop_magics[i], blockers)]
     }
+
+    // Size of the attack tables, which make up almost all of the memory used
+    pub fn memory_bytes(&self) -> usize {
+        self.rook_tables
+            .iter()
+            .chain(&self.bishop_tables)
+            .map(|table| table.len() * std::mem::size_of::<Bitboard>())
+            .sum()
+    }
 }
 
 impl Default for SlidingMoveGen {
@@ -95,39 +104,25 @@ impl Direction {
 
     fn moves(&self, square: Square, blockers: Bitboard) -> Bitboard {
         match self {
-            Direction::Orthogonal => Self::sliding_moves(square, blockers, [1, 8, -1, -8]),
-            Direction::Diagonal => Self::sliding_moves(square, blockers, [7, 9, -7, -9]),
+            Direction::Orthogonal => {
+                Self::sliding_moves(square, blockers, [(1, 0), (0, 1), (-1, 0), (0, -1)])
+            }
+            Direction::Diagonal => {
+                Self::sliding_moves(square, blockers, [(1, 1), (1, -1), (-1, 1), (-1, -1)])
+            }
         }
     }
 
-    fn sliding_moves(square: Square, blockers: Bitboard, offsets: [i8; 4]) -> Bitboard {
-        let i = square as i8;
-
+    fn sliding_moves(square: Square, blockers: Bitboard, steps: [(i8, i8); 4]) -> Bitboard {
         let mut mask = Bitboard::EMPTY;
 
-        for off in offsets {
-            let mut origin = i;
-
-            while in_bounds(origin) {
-                let (origin_rank, origin_file) = coords(origin as u8);
-
-                let target = origin + off;
-                if !in_bounds(target) {
-                    break;
-                }
-
-                let (rank, file) = coords(target as u8);
-                if origin_rank.abs_diff(rank) > 1 || origin_file.abs_diff(file) > 1 {
-                    break;
-                }
-
-                mask |= Bitboard(1 << target);
+        for (dr, df) in steps {
+            for target in square.ray(dr, df) {
+                mask |= target.bitboard();
 
-                if (1 << target) & blockers.0 > 0 {
+                if !(target.bitboard() & blockers).is_empty() {
                     break;
                 }
-
-                origin = target;
             }
         }
 
@@ -212,6 +207,37 @@ fn generate_magic(
 mod tests {
     use super::*;
 
+    // Cross-checks the magic lookups against the naive ray walk
+    #[test]
+    fn test_magic_lookups_match_ray_walk() {
+        let smg = SlidingMoveGen::new();
+
+        for square in Square::ALL {
+            for _ in 0..1000 {
+                // Dense and sparse occupancies
+                for blockers in [Bitboard(random_u64()), Bitboard(random_magic())] {
+                    assert_eq!(
+                        smg.rook_moves(square, blockers),
+                        Direction::Orthogonal.moves(square, blockers),
+                        "rook moves from {square} with blockers {blockers:?}"
+                    );
+                    assert_eq!(
+                        smg.bishop_moves(square, blockers),
+                        Direction::Diagonal.moves(square, blockers),
+                        "bishop moves from {square} with blockers {blockers:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_memory_bytes() {
+        let bytes = SlidingMoveGen::new().memory_bytes();
+
+        assert!((1 << 20..4 << 20).contains(&bytes), "{bytes} bytes");
+    }
+
     #[test]
     fn test_rook_sliding_moves() {
         let square = Square::D4;
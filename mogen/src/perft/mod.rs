@@ -1,52 +1,359 @@
+use std::collections::BTreeMap;
+
 use crate::{
-    board::{r#move::Move, Board},
+    board::{bitboard::Bitboard, color::Color, piece::Piece, r#move::Move, Board},
     MoveGen,
 };
 
-pub fn perft_inner(board: &Board, depth: u8, move_gen: &MoveGen) -> u32 {
-    let mut moves = Vec::new();
+// Which moves perft follows at each node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerftMoves {
+    Pseudolegal,
+    Legal,
+    // Pseudolegal moves kept only if making them doesn't leave the king in
+    // check. Slower, but independent of pin and check detection.
+    ByMakeMove,
+}
 
-    move_gen.pseudolegal_moves(board, &mut moves);
-    let mut count = 0;
+impl PerftMoves {
+    fn generate(self, board: &Board, move_gen: &MoveGen, moves: &mut Vec<Move>) {
+        match self {
+            PerftMoves::Pseudolegal => move_gen.pseudolegal_moves(board, moves),
+            PerftMoves::Legal => move_gen.legal_moves(board, moves),
+            PerftMoves::ByMakeMove => {
+                move_gen.pseudolegal_moves(board, moves);
+                moves.retain(|&mv| move_gen.is_legal_by_make_move(board, mv));
+            }
+        }
+    }
+}
+
+// Hooks into the perft recursion, which do nothing by default
+pub trait PerftVisitor {
+    // Lets the last ply be counted without making its moves
+    const COUNT_ONLY: bool = false;
+
+    // Every move made, with the boards before and after it
+    fn visit_move(&mut self, _before: &Board, _mv: Move, _after: &Board) {}
+
+    // Every leaf (depth 0) position
+    fn visit_leaf(&mut self, _board: &Board) {}
+}
+
+pub struct CountOnly;
 
+impl PerftVisitor for CountOnly {
+    const COUNT_ONLY: bool = true;
+}
+
+struct LeafVisitor<F>(F);
+
+impl<F: FnMut(&Board)> PerftVisitor for LeafVisitor<F> {
+    fn visit_leaf(&mut self, board: &Board) {
+        (self.0)(board);
+    }
+}
+
+struct MakeMoveChecker;
+
+impl PerftVisitor for MakeMoveChecker {
+    fn visit_move(&mut self, before: &Board, mv: Move, after: &Board) {
+        check_make_move(before, mv, after);
+    }
+}
+
+// Counts the leaves of the move tree, following `moves` at each node
+pub fn perft_with<V: PerftVisitor>(
+    board: &Board,
+    depth: u8,
+    move_gen: &MoveGen,
+    moves: PerftMoves,
+    visitor: &mut V,
+) -> u64 {
     if depth == 0 {
+        visitor.visit_leaf(board);
         return 1;
     }
 
-    if depth == 1 {
-        return moves.len() as u32;
+    let mut list = Vec::new();
+    moves.generate(board, move_gen, &mut list);
+
+    if depth == 1 && V::COUNT_ONLY {
+        return list.len() as u64;
     }
 
-    for mv in &moves {
-        let board = board.make_move(*mv);
-        count += perft_inner(&board, depth - 1, move_gen);
+    let mut count = 0;
+    for mv in list {
+        let after = board.make_move(mv);
+        visitor.visit_move(board, mv, &after);
+        count += perft_with(&after, depth - 1, move_gen, moves, visitor);
     }
 
     count
 }
 
-pub fn perft(board: &Board, depth: u8) -> u32 {
+pub fn perft_inner(board: &Board, depth: u8, move_gen: &MoveGen) -> u64 {
+    perft_with(
+        board,
+        depth,
+        move_gen,
+        PerftMoves::Pseudolegal,
+        &mut CountOnly,
+    )
+}
+
+pub fn perft(board: &Board, depth: u8) -> u64 {
     let move_gen = MoveGen::new();
     perft_inner(board, depth, &move_gen)
 }
 
-pub fn divide_inner(board: &Board, depth: u8, move_gen: &MoveGen) -> Vec<(u32, Move)> {
-    let mut results = Vec::new();
-
+pub fn divide_inner(board: &Board, depth: u8, move_gen: &MoveGen) -> Vec<(u64, Move)> {
     let mut moves = Vec::new();
     move_gen.pseudolegal_moves(board, &mut moves);
 
-    for mv in moves {
-        let perft_result = perft_inner(board, depth - 1, move_gen);
-        let result = (perft_result, mv);
+    moves
+        .into_iter()
+        .map(|mv| (perft_from_move(board, mv, depth - 1, move_gen), mv))
+        .collect()
+}
+
+pub fn divide(board: &Board, depth: u8) -> Vec<(u64, Move)> {
+    let move_gen = MoveGen::new();
+    divide_inner(board, depth, &move_gen)
+}
+
+// Perft of the subtree below `mv`, i.e. its entry in a divide one ply deeper
+pub fn perft_from_move(board: &Board, mv: Move, depth: u8, move_gen: &MoveGen) -> u64 {
+    perft_inner(&board.make_move(mv), depth, move_gen)
+}
+
+// Counts leaves of the legal move tree, using `MoveGen::legal_moves`
+pub fn legal_perft(board: &Board, depth: u8) -> u64 {
+    let move_gen = MoveGen::new();
+    perft_with(board, depth, &move_gen, PerftMoves::Legal, &mut CountOnly)
+}
+
+pub fn perft_visit_inner<F: FnMut(&Board)>(
+    board: &Board,
+    depth: u8,
+    move_gen: &MoveGen,
+    visit: &mut F,
+) {
+    let mut visitor = LeafVisitor(visit);
+    perft_with(
+        board,
+        depth,
+        move_gen,
+        PerftMoves::Pseudolegal,
+        &mut visitor,
+    );
+}
+
+// Calls `visit` on every leaf (depth 0) position of the perft tree
+pub fn perft_visit<F: FnMut(&Board)>(board: &Board, depth: u8, visit: &mut F) {
+    let move_gen = MoveGen::new();
+    perft_visit_inner(board, depth, &move_gen, visit);
+}
+
+impl Board {
+    // Legal node count below each root move, keyed (and sorted) by UCI string.
+    // Checks legality by making each move, so it can serve as a reference.
+    pub fn perft_divide(&self, depth: u8, move_gen: &MoveGen) -> BTreeMap<String, u64> {
+        let mut results = BTreeMap::new();
+
+        if depth == 0 {
+            return results;
+        }
+
+        let mut moves = Vec::new();
+        PerftMoves::ByMakeMove.generate(self, move_gen, &mut moves);
 
-        results.push(result);
+        for mv in moves {
+            let count = perft_with(
+                &self.make_move(mv),
+                depth - 1,
+                move_gen,
+                PerftMoves::ByMakeMove,
+                &mut CountOnly,
+            );
+            results.insert(mv.to_string(), count);
+        }
+
+        results
+    }
+}
+
+// Panics if applying `mv` to `before` produced an inconsistent board
+fn check_make_move(before: &Board, mv: Move, after: &Board) {
+    let context = format!("after {mv} in {}", before.fen());
+
+    let mut pieces = Bitboard::EMPTY;
+    for piece in Piece::ALL {
+        let bitboard = after.piece_bitboard(piece);
+        assert!(
+            (pieces & bitboard).is_empty(),
+            "overlapping piece bitboards {context}"
+        );
+        pieces |= bitboard;
     }
 
-    results
+    let white = after.color_bitboard(Color::White);
+    let black = after.color_bitboard(Color::Black);
+    assert!(
+        (white & black).is_empty(),
+        "overlapping color bitboards {context}"
+    );
+    assert_eq!(pieces, white | black, "piece/color mismatch {context}");
+
+    for color in [Color::White, Color::Black] {
+        assert_eq!(
+            before.piece_count(Piece::King, color),
+            after.piece_count(Piece::King, color),
+            "king count changed {context}"
+        );
+    }
+
+    let captured = before.all_pieces().count() - after.all_pieces().count();
+    assert!(captured <= 1, "more than one piece removed {context}");
+    assert_eq!(
+        after.active_color,
+        before.active_color.inverse(),
+        "side to move not flipped {context}"
+    );
 }
 
-pub fn divide(board: &Board, depth: u8) -> Vec<(u32, Move)> {
+pub fn perft_check_inner(board: &Board, depth: u8, move_gen: &MoveGen) -> u64 {
+    perft_with(
+        board,
+        depth,
+        move_gen,
+        PerftMoves::Legal,
+        &mut MakeMoveChecker,
+    )
+}
+
+// Legal perft that validates every board produced by `make_move`, panicking on corruption
+pub fn perft_check(board: &Board, depth: u8) -> u64 {
     let move_gen = MoveGen::new();
-    divide_inner(board, depth, &move_gen)
+    perft_check_inner(board, depth, &move_gen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perft_visit() {
+        let board = Board::default();
+
+        for depth in 0..4 {
+            let mut leaves = 0;
+            perft_visit(&board, depth, &mut |_| leaves += 1);
+
+            assert_eq!(leaves, perft(&board, depth));
+        }
+    }
+
+    #[test]
+    fn test_divide() {
+        let results = divide(&Board::default(), 3);
+
+        assert_eq!(results.len(), 20);
+        assert_eq!(results.iter().map(|(count, _)| count).sum::<u64>(), 8902);
+    }
+
+    #[test]
+    fn test_perft_from_move() {
+        let move_gen = MoveGen::new();
+        let board = Board::default();
+        let e2e4 = Move::try_from("e2e4").unwrap();
+
+        let (expected, _) = divide_inner(&board, 3, &move_gen)
+            .into_iter()
+            .find(|(_, mv)| *mv == e2e4)
+            .unwrap();
+
+        assert_eq!(perft_from_move(&board, e2e4, 2, &move_gen), expected);
+        assert_eq!(perft_from_move(&board, e2e4, 1, &move_gen), 20);
+    }
+
+    #[test]
+    fn test_perft_divide() {
+        let move_gen = MoveGen::new();
+        let results = Board::default().perft_divide(2, &move_gen);
+
+        assert_eq!(results.len(), 20);
+        assert!(results.values().all(|count| *count == 20));
+        assert_eq!(results.keys().next().map(String::as_str), Some("a2a3"));
+        assert_eq!(results.get("g1f3"), Some(&20));
+
+        // Only legal moves are listed
+        let board = Board::from_fen("4k3/8/8/8/8/8/3r4/4K3 w - - 0 1").unwrap();
+        let results = board.perft_divide(1, &move_gen);
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["e1d2", "e1f1"]);
+    }
+
+    #[test]
+    fn test_perft_check() {
+        let kiwipete =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+
+        assert!(perft_check(&kiwipete, 3) > 0);
+        assert_eq!(perft_check(&Board::default(), 3), 8902);
+    }
+
+    // Node counts from https://www.chessprogramming.org/Perft_Results
+    fn assert_perft(fen: &str, expected: &[u64]) {
+        let move_gen = MoveGen::new();
+        let board = Board::from_fen(fen).unwrap();
+
+        for (depth, nodes) in (1..).zip(expected) {
+            assert_eq!(
+                perft_with(&board, depth, &move_gen, PerftMoves::Legal, &mut CountOnly),
+                *nodes,
+                "depth {depth} of {fen}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_perft_startpos() {
+        assert_perft(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8902, 197281, 4865609],
+        );
+    }
+
+    #[test]
+    fn test_perft_kiwipete() {
+        assert_perft(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[48, 2039, 97862, 4085603],
+        );
+    }
+
+    #[test]
+    fn test_perft_position_3() {
+        assert_perft(
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            &[14, 191, 2812, 43238, 674624],
+        );
+    }
+
+    #[test]
+    fn test_perft_position_4() {
+        assert_perft(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            &[6, 264, 9467, 422333],
+        );
+    }
+
+    #[test]
+    fn test_perft_position_5() {
+        assert_perft(
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            &[44, 1486, 62379, 2103487],
+        );
+    }
 }
@@ -0,0 +1,273 @@
+use std::io::{self, BufRead};
+
+use crate::{board::Board, game::Game, MoveGen};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnError {
+    Io(io::ErrorKind),
+    BadTag(String),
+    BadFen(String),
+    IllegalMove(String),
+}
+
+pub(crate) const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+pub struct GameReader<'a, R: BufRead> {
+    lines: io::Lines<R>,
+    move_gen: &'a MoveGen,
+    // Tag line that started the next game while reading the current one
+    pending: Option<String>,
+}
+
+// Lazily parses every game in a (possibly multi-game) PGN
+pub fn read_games<R: BufRead>(reader: R, move_gen: &MoveGen) -> GameReader<'_, R> {
+    GameReader {
+        lines: reader.lines(),
+        move_gen,
+        pending: None,
+    }
+}
+
+impl<R: BufRead> Iterator for GameReader<'_, R> {
+    type Item = Result<Game, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut tags = Vec::new();
+        let mut movetext = String::new();
+        let mut bad_tag = None;
+
+        loop {
+            let line = match self.pending.take().map(Ok).or_else(|| self.lines.next()) {
+                Some(Ok(line)) => line,
+                Some(Err(err)) => return Some(Err(PgnError::Io(err.kind()))),
+                None => break,
+            };
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                // A tag after movetext belongs to the next game, as does an
+                // Event tag once this game is known to be broken
+                if !movetext.is_empty() || (bad_tag.is_some() && line.starts_with("[Event ")) {
+                    self.pending = Some(line.to_owned());
+                    break;
+                }
+
+                // The rest of the game is still read, so its remaining tags and
+                // movetext aren't taken as the start of the next game
+                match parse_tag(line) {
+                    Some(tag) => tags.push(tag),
+                    None => bad_tag = bad_tag.or_else(|| Some(line.to_owned())),
+                }
+                continue;
+            }
+
+            movetext.push_str(line);
+            movetext.push('\n');
+
+            if line
+                .split_whitespace()
+                .last()
+                .is_some_and(|token| RESULTS.contains(&token))
+            {
+                break;
+            }
+        }
+
+        if let Some(line) = bad_tag {
+            return Some(Err(PgnError::BadTag(line)));
+        }
+
+        if tags.is_empty() && movetext.is_empty() {
+            return None;
+        }
+
+        Some(build_game(tags, &movetext, self.move_gen))
+    }
+}
+
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    Some((name.to_owned(), value.replace("\\\"", "\"")))
+}
+
+fn build_game(
+    tags: Vec<(String, String)>,
+    movetext: &str,
+    move_gen: &MoveGen,
+) -> Result<Game, PgnError> {
+    let start = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => Board::from_fen(fen).map_err(|_| PgnError::BadFen(fen.clone()))?,
+        None => Board::default(),
+    };
+
+    let mut game = Game::new(start);
+    for (name, value) in &tags {
+        game.set_tag(name, value);
+    }
+
+    for token in movetext_tokens(movetext) {
+        if RESULTS.contains(&token.as_str()) {
+            break;
+        }
+
+        if game.push_san(&token, move_gen).is_err() {
+            return Err(PgnError::IllegalMove(token));
+        }
+    }
+
+    Ok(game)
+}
+
+// SAN tokens and results of the mainline, skipping comments, variations,
+// NAGs and move numbers
+pub(crate) fn movetext_tokens(movetext: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut variation_depth = 0;
+    let mut chars = movetext.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' | ';' | '(' | ')' => {
+                push_token(&mut tokens, &mut current);
+
+                match ch {
+                    '{' => chars.by_ref().take_while(|c| *c != '}').for_each(drop),
+                    ';' => chars.by_ref().take_while(|c| *c != '\n').for_each(drop),
+                    '(' => variation_depth += 1,
+                    _ => variation_depth = 0.max(variation_depth - 1),
+                }
+            }
+            _ if ch.is_whitespace() => push_token(&mut tokens, &mut current),
+            _ if variation_depth > 0 => (),
+            _ => current.push(ch),
+        }
+    }
+    push_token(&mut tokens, &mut current);
+
+    tokens
+}
+
+fn push_token(tokens: &mut Vec<String>, current: &mut String) {
+    let token = std::mem::take(current);
+
+    // Strip move numbers such as "12." or "12...", which may be glued to the move
+    let digits = token.chars().take_while(char::is_ascii_digit).count();
+    let token = if token[digits..].starts_with('.') {
+        token[digits..].trim_start_matches('.')
+    } else {
+        token.as_str()
+    };
+
+    let is_annotation = token.starts_with('$') || token.chars().all(|c| c == '!' || c == '?');
+    if !token.is_empty() && !is_annotation {
+        tokens.push(token.to_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PGN: &str = r#"[Event "First"]
+[White "A"]
+[Black "B"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bb5 {The Ruy Lopez} a6 1-0
+
+[Event "Second"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"]
+[Result "*"]
+
+1.e4 Kd7 (1... Ke7 2. e5) 2. Kd2 $1 ; a comment
+2... Kd6 3.e5+ *
+"#;
+
+    fn placement(board: &Board) -> String {
+        board.fen().split(' ').next().unwrap().to_owned()
+    }
+
+    #[test]
+    fn test_read_games() {
+        let move_gen = MoveGen::new();
+        let games = read_games(PGN.as_bytes(), &move_gen)
+            .collect::<Result<Vec<Game>, PgnError>>()
+            .unwrap();
+
+        assert_eq!(games.len(), 2);
+
+        assert_eq!(games[0].tag("Event"), Some("First"));
+        assert_eq!(games[0].moves().len(), 6);
+        assert_eq!(
+            placement(games[0].board()),
+            "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R"
+        );
+
+        assert_eq!(games[1].tag("Event"), Some("Second"));
+        assert_eq!(games[1].moves().len(), 5);
+        assert_eq!(placement(games[1].board()), "8/8/3k4/4P3/8/8/3K4/8");
+    }
+
+    #[test]
+    fn test_read_games_errors() {
+        let move_gen = MoveGen::new();
+
+        let mut games = read_games("1. e4 e4 *".as_bytes(), &move_gen);
+        assert_eq!(
+            games.next(),
+            Some(Err(PgnError::IllegalMove("e4".to_owned())))
+        );
+        assert_eq!(games.next(), None);
+
+        let mut games = read_games("[Event First]\n1. e4 *".as_bytes(), &move_gen);
+        assert_eq!(
+            games.next(),
+            Some(Err(PgnError::BadTag("[Event First]".to_owned())))
+        );
+        assert_eq!(games.next(), None);
+    }
+
+    #[test]
+    fn test_read_games_after_bad_tag() {
+        let move_gen = MoveGen::new();
+        let pgn = "[Event First]\n[Result \"*\"]\n\n1. e4 e5 *\n\n[Event \"Second\"]\n\n1. d4 *\n";
+
+        let mut games = read_games(pgn.as_bytes(), &move_gen);
+        assert_eq!(
+            games.next(),
+            Some(Err(PgnError::BadTag("[Event First]".to_owned())))
+        );
+
+        let game = games.next().unwrap().unwrap();
+        assert_eq!(game.tag("Event"), Some("Second"));
+        assert_eq!(game.moves().len(), 1);
+        assert_eq!(games.next(), None);
+
+        // A broken game without movetext ends at the next Event tag
+        let pgn = "[Event \"First\"]\n[Date 2024]\n\n[Event \"Second\"]\n\n1. d4 *\n";
+
+        let mut games = read_games(pgn.as_bytes(), &move_gen);
+        assert_eq!(
+            games.next(),
+            Some(Err(PgnError::BadTag("[Date 2024]".to_owned())))
+        );
+        assert_eq!(games.next().unwrap().unwrap().tag("Event"), Some("Second"));
+        assert_eq!(games.next(), None);
+    }
+
+    #[test]
+    fn test_movetext_tokens() {
+        assert_eq!(
+            movetext_tokens("1.e4 {x (y)} e5!? 2. O-O (2. d4 (2. c4)) 0-0 $3 1/2-1/2"),
+            vec!["e4", "e5!?", "O-O", "0-0", "1/2-1/2"]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::ops::{Add, Neg, Sub};
+
+// Centipawn score from the side to move's perspective.
+// Scores within MAX_MATE_PLY of +-MATE encode a forced mate, where the distance
+// from MATE is the number of plies until mate is delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(pub i32);
+
+impl Score {
+    pub const ZERO: Score = Score(0);
+    pub const MATE: i32 = 32000;
+    pub const MAX_MATE_PLY: i32 = 256;
+    // Largest (absolute) score that is not a mate score
+    pub const MAX_EVAL: i32 = Self::MATE - Self::MAX_MATE_PLY - 1;
+
+    pub fn new(centipawns: i32) -> Score {
+        Score(centipawns.clamp(-Self::MAX_EVAL, Self::MAX_EVAL))
+    }
+
+    // Side to move delivers mate in `ply` plies
+    pub fn mate_in(ply: u8) -> Score {
+        Score(Self::MATE - ply as i32)
+    }
+
+    // Side to move gets mated in `ply` plies
+    pub fn mated_in(ply: u8) -> Score {
+        Score(-Self::MATE + ply as i32)
+    }
+
+    pub fn is_mate(&self) -> bool {
+        self.0.abs() > Self::MAX_EVAL
+    }
+
+    // Plies until mate; positive if the side to move mates, negative if it gets mated
+    pub fn mate_distance(&self) -> Option<i32> {
+        if !self.is_mate() {
+            return None;
+        }
+
+        if self.0 > 0 {
+            Some(Self::MATE - self.0)
+        } else {
+            Some(-(Self::MATE + self.0))
+        }
+    }
+}
+
+impl Neg for Score {
+    type Output = Score;
+
+    fn neg(self) -> Self::Output {
+        Score(-self.0)
+    }
+}
+
+// Mate scores absorb evaluation terms, and normal scores saturate before reaching the mate range
+impl Add for Score {
+    type Output = Score;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.is_mate() {
+            return self;
+        }
+        if rhs.is_mate() {
+            return rhs;
+        }
+
+        Score::new(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Score {
+    type Output = Score;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mate_negation() {
+        let mate = Score::mate_in(3);
+
+        assert!(mate.is_mate());
+        assert_eq!(mate.mate_distance(), Some(3));
+
+        assert_eq!(-mate, Score::mated_in(3));
+        assert_eq!((-mate).mate_distance(), Some(-3));
+        assert_eq!(-(-mate), mate);
+
+        assert!(!Score::new(150).is_mate());
+        assert_eq!(Score::new(150).mate_distance(), None);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let best_eval = Score::new(i32::MAX);
+        let worst_eval = Score::new(i32::MIN);
+
+        assert!(!best_eval.is_mate());
+        assert!(Score::mate_in(200) > best_eval);
+        assert!(Score::mate_in(1) > Score::mate_in(2));
+        assert!(Score::mated_in(200) < worst_eval);
+        assert!(Score::mated_in(1) < Score::mated_in(2));
+    }
+
+    #[test]
+    fn test_saturating_arithmetic() {
+        assert_eq!(Score::new(100) + Score::new(50), Score(150));
+        assert_eq!(Score::new(100) - Score::new(50), Score(50));
+
+        let near_max = Score::new(Score::MAX_EVAL - 10);
+        assert_eq!(near_max + Score::new(100), Score(Score::MAX_EVAL));
+        assert!(!(near_max + Score::new(100)).is_mate());
+
+        assert_eq!(Score::mate_in(5) + Score::new(300), Score::mate_in(5));
+        assert_eq!(Score::new(300) - Score::mate_in(5), Score::mated_in(5));
+    }
+}
@@ -39,30 +39,13 @@ pub fn knight_move_mask(square: Square) -> Bitboard {
     mask
 }
 
-pub fn sliding_move_mask(square: Square, offsets: &[i8]) -> Bitboard {
-    let i = square as i8;
-
+// Every square reached by stepping from `square` in each (rank, file) direction
+pub fn sliding_move_mask(square: Square, directions: &[(i8, i8)]) -> Bitboard {
     let mut mask = Bitboard::EMPTY;
 
-    for off in offsets {
-        let mut origin = i;
-
-        while in_bounds(origin) {
-            let (origin_rank, origin_file) = coords(origin as u8);
-
-            let target = origin + off;
-            if !in_bounds(target) {
-                break;
-            }
-
-            let (rank, file) = coords(target as u8);
-            if origin_rank.abs_diff(rank) > 1 || origin_file.abs_diff(file) > 1 {
-                break;
-            }
-
-            mask |= Bitboard(1 << target);
-
-            origin = target;
+    for &(dr, df) in directions {
+        for target in square.ray(dr, df) {
+            mask |= target.bitboard();
         }
     }
 
@@ -70,11 +53,11 @@ pub fn sliding_move_mask(square: Square, offsets: &[i8]) -> Bitboard {
 }
 
 pub fn bishop_move_mask(square: Square) -> Bitboard {
-    sliding_move_mask(square, &[-7, -9, 7, 9])
+    sliding_move_mask(square, &[(1, 1), (1, -1), (-1, 1), (-1, -1)])
 }
 
 pub fn rook_move_mask(square: Square) -> Bitboard {
-    sliding_move_mask(square, &[1, 8, -1, -8])
+    sliding_move_mask(square, &[(1, 0), (-1, 0), (0, 1), (0, -1)])
 }
 
 pub fn king_move_mask(square: Square) -> Bitboard {
@@ -108,32 +91,33 @@ pub fn pawn_capture_mask(square: Square, color: Color) -> Bitboard {
         return Bitboard::EMPTY;
     }
 
-    let offsets = match color {
-        Color::White => [7, 9],
-        Color::Black => [-7, -9],
+    let dr = match color {
+        Color::White => 1,
+        Color::Black => -1,
     };
 
-    let (source_rank, source_file) = coords(square as u8);
-
     let mut mask = Bitboard::EMPTY;
 
-    for off in offsets {
-        let target = square as i8 + off;
-
-        if !in_bounds(target) {
-            continue;
+    for df in [-1, 1] {
+        if let Some(target) = square.ray(dr, df).next() {
+            mask |= target.bitboard();
         }
+    }
 
-        let (rank, file) = coords(target as u8);
-
-        if source_rank.abs_diff(rank) != 1 || source_file.abs_diff(file) != 1 {
-            continue;
-        }
+    mask
+}
 
-        mask |= Bitboard(1 << target);
+pub fn pawn_push_mask(square: Square, color: Color) -> Bitboard {
+    if (square as u8) < 8 || (square as u8) > 55 {
+        return Bitboard::EMPTY;
     }
 
-    mask
+    let target = match color {
+        Color::White => square as u8 + 8,
+        Color::Black => square as u8 - 8,
+    };
+
+    Bitboard(1 << target)
 }
 
 pub fn generate_knight_masks() -> [Bitboard; 64] {
@@ -176,10 +160,48 @@ pub fn generate_pawn_capture_masks(color: Color) -> [Bitboard; 64] {
     masks
 }
 
+pub fn generate_pawn_push_masks(color: Color) -> [Bitboard; 64] {
+    let mut masks = [Bitboard::EMPTY; 64];
+    for square in Square::ALL {
+        masks[square as usize] = pawn_push_mask(square, color);
+    }
+    masks
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::r#static::move_masks::{
+        BISHOP_MOVE_MASKS, BLACK_PAWN_CAPTURE_MASKS, BLACK_PAWN_PUSH_MASKS, KING_MOVE_MASKS,
+        KNIGHT_MOVE_MASKS, ROOK_MOVE_MASKS, WHITE_PAWN_CAPTURE_MASKS, WHITE_PAWN_PUSH_MASKS,
+    };
+
     use super::*;
 
+    // Fails if move_masks.rs is stale; rerun the generate_static binary to fix it
+    #[test]
+    fn test_static_masks_up_to_date() {
+        assert_eq!(generate_knight_masks(), KNIGHT_MOVE_MASKS);
+        assert_eq!(generate_bishop_masks(), BISHOP_MOVE_MASKS);
+        assert_eq!(generate_rook_masks(), ROOK_MOVE_MASKS);
+        assert_eq!(generate_king_masks(), KING_MOVE_MASKS);
+        assert_eq!(
+            generate_pawn_capture_masks(Color::White),
+            WHITE_PAWN_CAPTURE_MASKS
+        );
+        assert_eq!(
+            generate_pawn_capture_masks(Color::Black),
+            BLACK_PAWN_CAPTURE_MASKS
+        );
+        assert_eq!(
+            generate_pawn_push_masks(Color::White),
+            WHITE_PAWN_PUSH_MASKS
+        );
+        assert_eq!(
+            generate_pawn_push_masks(Color::Black),
+            BLACK_PAWN_PUSH_MASKS
+        );
+    }
+
     #[test]
     fn test_coords() {
         assert_eq!(coords(Square::B7 as u8), (6, 1));
@@ -363,4 +385,25 @@ mod tests {
             assert_eq!(Bitboard::EMPTY, pawn_capture_mask(*square, Color::Black));
         }
     }
+
+    #[test]
+    fn test_pawn_push_mask() {
+        assert_eq!(
+            Square::E3.bitboard(),
+            pawn_push_mask(Square::E2, Color::White)
+        );
+        assert_eq!(
+            Square::E1.bitboard(),
+            pawn_push_mask(Square::E2, Color::Black)
+        );
+        assert_eq!(
+            Square::A8.bitboard(),
+            pawn_push_mask(Square::A7, Color::White)
+        );
+
+        for square in Square::ALL[..8].iter().chain(&Square::ALL[56..]) {
+            assert_eq!(Bitboard::EMPTY, pawn_push_mask(*square, Color::White));
+            assert_eq!(Bitboard::EMPTY, pawn_push_mask(*square, Color::Black));
+        }
+    }
 }
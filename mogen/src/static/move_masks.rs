@@ -396,3 +396,135 @@ pub const BLACK_PAWN_CAPTURE_MASKS: [Bitboard; 64] = [
     Bitboard(0),
     Bitboard(0),
 ];
+pub const WHITE_PAWN_PUSH_MASKS: [Bitboard; 64] = [
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(65536),
+    Bitboard(131072),
+    Bitboard(262144),
+    Bitboard(524288),
+    Bitboard(1048576),
+    Bitboard(2097152),
+    Bitboard(4194304),
+    Bitboard(8388608),
+    Bitboard(16777216),
+    Bitboard(33554432),
+    Bitboard(67108864),
+    Bitboard(134217728),
+    Bitboard(268435456),
+    Bitboard(536870912),
+    Bitboard(1073741824),
+    Bitboard(2147483648),
+    Bitboard(4294967296),
+    Bitboard(8589934592),
+    Bitboard(17179869184),
+    Bitboard(34359738368),
+    Bitboard(68719476736),
+    Bitboard(137438953472),
+    Bitboard(274877906944),
+    Bitboard(549755813888),
+    Bitboard(1099511627776),
+    Bitboard(2199023255552),
+    Bitboard(4398046511104),
+    Bitboard(8796093022208),
+    Bitboard(17592186044416),
+    Bitboard(35184372088832),
+    Bitboard(70368744177664),
+    Bitboard(140737488355328),
+    Bitboard(281474976710656),
+    Bitboard(562949953421312),
+    Bitboard(1125899906842624),
+    Bitboard(2251799813685248),
+    Bitboard(4503599627370496),
+    Bitboard(9007199254740992),
+    Bitboard(18014398509481984),
+    Bitboard(36028797018963968),
+    Bitboard(72057594037927936),
+    Bitboard(144115188075855872),
+    Bitboard(288230376151711744),
+    Bitboard(576460752303423488),
+    Bitboard(1152921504606846976),
+    Bitboard(2305843009213693952),
+    Bitboard(4611686018427387904),
+    Bitboard(9223372036854775808),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+];
+pub const BLACK_PAWN_PUSH_MASKS: [Bitboard; 64] = [
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(1),
+    Bitboard(2),
+    Bitboard(4),
+    Bitboard(8),
+    Bitboard(16),
+    Bitboard(32),
+    Bitboard(64),
+    Bitboard(128),
+    Bitboard(256),
+    Bitboard(512),
+    Bitboard(1024),
+    Bitboard(2048),
+    Bitboard(4096),
+    Bitboard(8192),
+    Bitboard(16384),
+    Bitboard(32768),
+    Bitboard(65536),
+    Bitboard(131072),
+    Bitboard(262144),
+    Bitboard(524288),
+    Bitboard(1048576),
+    Bitboard(2097152),
+    Bitboard(4194304),
+    Bitboard(8388608),
+    Bitboard(16777216),
+    Bitboard(33554432),
+    Bitboard(67108864),
+    Bitboard(134217728),
+    Bitboard(268435456),
+    Bitboard(536870912),
+    Bitboard(1073741824),
+    Bitboard(2147483648),
+    Bitboard(4294967296),
+    Bitboard(8589934592),
+    Bitboard(17179869184),
+    Bitboard(34359738368),
+    Bitboard(68719476736),
+    Bitboard(137438953472),
+    Bitboard(274877906944),
+    Bitboard(549755813888),
+    Bitboard(1099511627776),
+    Bitboard(2199023255552),
+    Bitboard(4398046511104),
+    Bitboard(8796093022208),
+    Bitboard(17592186044416),
+    Bitboard(35184372088832),
+    Bitboard(70368744177664),
+    Bitboard(140737488355328),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+    Bitboard(0),
+];
@@ -2,7 +2,7 @@ use std::{cmp::Ordering, collections::HashSet};
 
 use clap::{Parser, Subcommand};
 use mogen::{
-    board::{color::Color, piece::Piece, r#move::Move, square::Square, Board},
+    board::{color::Color, r#move::Move, square::Square, Board},
     MoveGen,
 };
 use mogen_test::perft;
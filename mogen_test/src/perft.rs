@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     io::{BufRead, BufReader, Write},
     process::{Command, Stdio},
 };
@@ -142,6 +142,9 @@ pub fn compare(board: &Board, depth: u8) -> CompareResult {
         }
     }
 
+    stdin.write_all(b"quit\n").unwrap();
+    stockfish.wait().unwrap();
+
     let (vec_results, mogen_total) = divide(board, depth);
 
     let mogen_results = {
@@ -159,3 +162,266 @@ pub fn compare(board: &Board, depth: u8) -> CompareResult {
         mogen_total,
     }
 }
+
+struct ReferenceDivide {
+    fen: &'static str,
+    depth: u8,
+    divide: &'static [(&'static str, u64)],
+}
+
+// Known-correct divide counts, checked against Stockfish
+const REFERENCE_DIVIDES: [ReferenceDivide; 2] = [
+    ReferenceDivide {
+        fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        depth: 3,
+        divide: &[
+            ("a2a3", 380),
+            ("a2a4", 420),
+            ("b1a3", 400),
+            ("b1c3", 440),
+            ("b2b3", 420),
+            ("b2b4", 421),
+            ("c2c3", 420),
+            ("c2c4", 441),
+            ("d2d3", 539),
+            ("d2d4", 560),
+            ("e2e3", 599),
+            ("e2e4", 600),
+            ("f2f3", 380),
+            ("f2f4", 401),
+            ("g1f3", 440),
+            ("g1h3", 400),
+            ("g2g3", 420),
+            ("g2g4", 421),
+            ("h2h3", 380),
+            ("h2h4", 420),
+        ],
+    },
+    ReferenceDivide {
+        fen: "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        depth: 3,
+        divide: &[
+            ("a5a4", 224),
+            ("a5a6", 240),
+            ("b4a4", 202),
+            ("b4b1", 265),
+            ("b4b2", 205),
+            ("b4b3", 248),
+            ("b4c4", 254),
+            ("b4d4", 243),
+            ("b4e4", 228),
+            ("b4f4", 41),
+            ("e2e3", 205),
+            ("e2e4", 177),
+            ("g2g3", 54),
+            ("g2g4", 226),
+        ],
+    },
+];
+
+// A move whose count differs from the reference, or which is missing on either side
+#[derive(Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub mv: String,
+    pub expected: Option<u64>,
+    pub found: Option<u64>,
+}
+
+// Returns `None` if the table has no entry for this position and depth
+pub fn compare_reference(board: &Board, depth: u8) -> Option<Vec<Divergence>> {
+    let reference = REFERENCE_DIVIDES.iter().find(|reference| {
+        reference.depth == depth
+            && Board::from_fen(reference.fen).is_ok_and(|other| other.same_position(board))
+    })?;
+
+    let move_gen = MoveGen::new();
+    let results = board.perft_divide(depth, &move_gen);
+
+    Some(diff_reference(reference.divide, &results))
+}
+
+fn diff_reference(reference: &[(&str, u64)], results: &BTreeMap<String, u64>) -> Vec<Divergence> {
+    let mut divergences = Vec::new();
+
+    for &(mv, expected) in reference {
+        let found = results.get(mv).copied();
+        if found != Some(expected) {
+            divergences.push(Divergence {
+                mv: mv.to_string(),
+                expected: Some(expected),
+                found,
+            });
+        }
+    }
+
+    for (mv, &found) in results {
+        if !reference.iter().any(|(reference_mv, _)| reference_mv == mv) {
+            divergences.push(Divergence {
+                mv: mv.clone(),
+                expected: None,
+                found: Some(found),
+            });
+        }
+    }
+
+    divergences
+}
+
+// Descends through the first move whose count differs between the two divide
+// functions, returning the line that leads to the deepest mismatch. Moves
+// missing on either side end the line, as does reaching depth 1.
+pub fn divergent_line<R, T>(
+    board: &Board,
+    depth: u8,
+    reference: &R,
+    tested: &T,
+) -> Option<Vec<Move>>
+where
+    R: Fn(&Board, u8) -> BTreeMap<String, u64>,
+    T: Fn(&Board, u8) -> BTreeMap<String, u64>,
+{
+    if depth == 0 {
+        return None;
+    }
+
+    let reference_divide = reference(board, depth);
+    let tested_divide = tested(board, depth);
+
+    let mut all_moves = reference_divide
+        .keys()
+        .chain(tested_divide.keys())
+        .collect::<Vec<&String>>();
+    all_moves.sort();
+    all_moves.dedup();
+
+    let diverging = all_moves
+        .into_iter()
+        .find(|mv| reference_divide.get(*mv) != tested_divide.get(*mv))?;
+    let mv = Move::try_from(diverging.as_str()).ok()?;
+
+    let mut line = vec![mv];
+
+    let in_both = reference_divide.contains_key(diverging) && tested_divide.contains_key(diverging);
+    if in_both && depth > 1 {
+        let next = board.make_move(mv);
+        if let Some(rest) = divergent_line(&next, depth - 1, reference, tested) {
+            line.extend(rest);
+        }
+    }
+
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Divide using `legal_moves`, optionally never generating `skipped`
+    fn generator_divide(
+        board: &Board,
+        depth: u8,
+        move_gen: &MoveGen,
+        skipped: Option<Move>,
+    ) -> BTreeMap<String, u64> {
+        fn count(board: &Board, depth: u8, move_gen: &MoveGen, skipped: Option<Move>) -> u64 {
+            if depth == 0 {
+                return 1;
+            }
+
+            let mut moves = Vec::new();
+            move_gen.legal_moves(board, &mut moves);
+            moves
+                .into_iter()
+                .filter(|mv| Some(*mv) != skipped)
+                .map(|mv| count(&board.make_move(mv), depth - 1, move_gen, skipped))
+                .sum()
+        }
+
+        let mut moves = Vec::new();
+        move_gen.legal_moves(board, &mut moves);
+
+        moves
+            .into_iter()
+            .filter(|mv| Some(*mv) != skipped)
+            .map(|mv| {
+                let nodes = count(&board.make_move(mv), depth - 1, move_gen, skipped);
+                (mv.to_string(), nodes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_divergent_line() {
+        let move_gen = MoveGen::new();
+        let board = Board::default();
+        let reference = |board: &Board, depth: u8| board.perft_divide(depth, &move_gen);
+
+        let correct = |board: &Board, depth: u8| generator_divide(board, depth, &move_gen, None);
+        assert_eq!(divergent_line(&board, 3, &reference, &correct), None);
+
+        // Never generating ...e5 first shows up after a2a3, the first root move
+        let e7e5 = Some(Move::try_from("e7e5").unwrap());
+        let broken = |board: &Board, depth: u8| generator_divide(board, depth, &move_gen, e7e5);
+        assert_eq!(
+            divergent_line(&board, 3, &reference, &broken),
+            Some(vec![
+                Move::try_from("a2a3").unwrap(),
+                Move::try_from("e7e5").unwrap(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_compare_reference() {
+        for reference in &REFERENCE_DIVIDES {
+            let board = Board::from_fen(reference.fen).unwrap();
+            assert_eq!(
+                compare_reference(&board, reference.depth),
+                Some(Vec::new()),
+                "{}",
+                reference.fen
+            );
+        }
+
+        // No entry for this depth
+        assert_eq!(compare_reference(&Board::default(), 1), None);
+    }
+
+    #[test]
+    fn test_diff_reference_flags_broken_generator() {
+        let reference = &REFERENCE_DIVIDES[0];
+        let mut results = reference
+            .divide
+            .iter()
+            .map(|&(mv, count)| (mv.to_string(), count))
+            .collect::<BTreeMap<String, u64>>();
+
+        assert!(diff_reference(reference.divide, &results).is_empty());
+
+        // Drop one move, miscount another and invent a third
+        results.remove("e2e4");
+        results.insert("d2d4".to_string(), 559);
+        results.insert("e1e2".to_string(), 20);
+
+        assert_eq!(
+            diff_reference(reference.divide, &results),
+            vec![
+                Divergence {
+                    mv: "d2d4".to_string(),
+                    expected: Some(560),
+                    found: Some(559),
+                },
+                Divergence {
+                    mv: "e2e4".to_string(),
+                    expected: Some(600),
+                    found: None,
+                },
+                Divergence {
+                    mv: "e1e2".to_string(),
+                    expected: None,
+                    found: Some(20),
+                },
+            ]
+        );
+    }
+}